@@ -0,0 +1,44 @@
+//! Compares rendering the same [`If`] with a lone `Then` through the fast
+//! path, which renders the branch directly, and through the general path,
+//! which a non-default `strategy` opts into without changing what is shown.
+
+use criterion::{
+  criterion_group,
+  criterion_main,
+  Criterion,
+};
+use leptos::*;
+use leptos_declarative::prelude::*;
+
+fn render_if(c: &mut Criterion) {
+  let mut group = c.benchmark_group("If with a lone Then");
+
+  group.bench_function("fast path", |b| {
+    b.iter(|| {
+      leptos::ssr::render_to_string(|| {
+        view! {
+        <If signal=true>
+          <Then>"A is true!"</Then>
+        </If>
+        }
+      })
+    })
+  });
+
+  group.bench_function("general path", |b| {
+    b.iter(|| {
+      leptos::ssr::render_to_string(|| {
+        view! {
+        <If signal=true strategy=IfStrategy::HighestPriority>
+          <Then>"A is true!"</Then>
+        </If>
+        }
+      })
+    })
+  });
+
+  group.finish();
+}
+
+criterion_group!(benches, render_if);
+criterion_main!(benches);
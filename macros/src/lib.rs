@@ -0,0 +1,87 @@
+#![deny(missing_docs)]
+
+//! Procedural macros for [`leptos_declarative`](https://docs.rs/leptos_declarative).
+//!
+//! These are re-exported by `leptos_declarative`, and should be used from
+//! there rather than by depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::{
+  format_ident,
+  quote,
+};
+use syn::{
+  parse_macro_input,
+  Data,
+  DeriveInput,
+  Fields,
+};
+
+/// Derives `MatchKey` for an enum.
+///
+/// This generates a `{Enum}Key` enum with the same visibility as `{Enum}` and
+/// one fieldless variant per variant of `{Enum}`, then implements `MatchKey`
+/// so each value maps to the key of its variant.
+#[proc_macro_derive(MatchKey)]
+pub fn derive_match_key(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+
+  let Data::Enum(data) = &input.data else {
+    return syn::Error::new_spanned(
+      &input.ident,
+      "`MatchKey` can only be derived for enums",
+    )
+    .into_compile_error()
+    .into();
+  };
+
+  let vis = &input.vis;
+  let ident = &input.ident;
+  let key_ident = format_ident!("{ident}Key");
+  let (impl_generics, ty_generics, where_clause) =
+    input.generics.split_for_impl();
+
+  let variants = data.variants.iter().map(|variant| &variant.ident);
+  let keys = variants
+    .clone()
+    .map(|variant| quote! { #key_ident::#variant });
+  let arms = data.variants.iter().map(|variant| {
+    let variant_ident = &variant.ident;
+
+    let pattern = match variant.fields {
+      Fields::Named(_) => quote! { { .. } },
+      Fields::Unnamed(_) => quote! { (..) },
+      Fields::Unit => quote! {},
+    };
+
+    quote! { Self::#variant_ident #pattern => #key_ident::#variant_ident }
+  });
+
+  let key_doc = format!("The [`MatchKey`] of [`{ident}`].");
+
+  quote! {
+    #[doc = #key_doc]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #vis enum #key_ident {
+      #(
+        #[allow(missing_docs)]
+        #variants,
+      )*
+    }
+
+    impl #impl_generics ::leptos_declarative::match_::MatchKey
+      for #ident #ty_generics #where_clause
+    {
+      type Key = #key_ident;
+
+      const KEYS: &'static [#key_ident] = &[#(#keys),*];
+
+      fn key(&self) -> #key_ident {
+        match self {
+          #(#arms,)*
+        }
+      }
+    }
+  }
+  .into()
+}
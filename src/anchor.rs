@@ -0,0 +1,189 @@
+//! Anchored positioning for [`leptos`].
+//!
+//! Floating content such as tooltips and popovers is usually rendered
+//! somewhere else entirely, e.g. through a
+//! [`GlobalPortalInput`](crate::portal::GlobalPortalInput), yet needs to stay
+//! next to the element it belongs to.
+//!
+//! Please refer to [`Anchored`] for usage examples.
+
+use leptos::{
+  html::ElementDescriptor,
+  *,
+};
+use std::rc::Rc;
+use wasm_bindgen::{
+  closure::Closure,
+  JsCast,
+};
+
+/// The size and position of an element relative to the viewport, in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+  /// The distance from the left edge of the viewport.
+  pub x: f64,
+  /// The distance from the top edge of the viewport.
+  pub y: f64,
+  /// The width.
+  pub width: f64,
+  /// The height.
+  pub height: f64,
+}
+
+impl From<web_sys::DomRect> for Rect {
+  fn from(rect: web_sys::DomRect) -> Self {
+    Self {
+      x: rect.x(),
+      y: rect.y(),
+      width: rect.width(),
+      height: rect.height(),
+    }
+  }
+}
+
+/// Where [`Anchored`] content is placed relative to its anchor.
+///
+/// Content is centered along the side of the anchor it is placed on.
+#[derive(Clone, Default)]
+pub enum Placement {
+  /// Above the anchor.
+  Top,
+  /// Below the anchor.
+  #[default]
+  Bottom,
+  /// To the left of the anchor.
+  Left,
+  /// To the right of the anchor.
+  Right,
+  /// Computes the top-left corner of the content, relative to the viewport,
+  /// from the anchor's and the content's [`Rect`]s.
+  Custom(Rc<dyn Fn(Rect, Rect) -> (f64, f64)>),
+}
+
+impl Placement {
+  /// Returns the top-left corner, relative to the viewport, at which
+  /// `content` should be placed next to `anchor`.
+  pub fn position(&self, anchor: Rect, content: Rect) -> (f64, f64) {
+    let center_x = anchor.x + (anchor.width - content.width) / 2.0;
+    let center_y = anchor.y + (anchor.height - content.height) / 2.0;
+
+    match self {
+      Self::Top => (center_x, anchor.y - content.height),
+      Self::Bottom => (center_x, anchor.y + anchor.height),
+      Self::Left => (anchor.x - content.width, center_y),
+      Self::Right => (anchor.x + anchor.width, center_y),
+      Self::Custom(position) => position(anchor, content),
+    }
+  }
+}
+
+/// Positions its children next to an `anchor` element, keeping them there as
+/// the page is scrolled or resized.
+///
+/// The children are wrapped in a `position: fixed` `<div>`, so this is meant
+/// to be rendered outside of any transformed or scrolling container, which is
+/// exactly what portals are for.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let button = create_node_ref::<html::Button>();
+///
+/// view! {
+///   <div>
+///     <button node_ref=button>"Hover me"</button>
+///
+///     <GlobalPortalInput>
+///       <Anchored anchor=button placement=Placement::Top>
+///         <p>"I follow the button around."</p>
+///       </Anchored>
+///     </GlobalPortalInput>
+///   </div>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn Anchored<El>(
+  /// The element the children are positioned next to.
+  anchor: NodeRef<El>,
+  /// Where the children are placed relative to `anchor`.
+  #[prop(optional)]
+  placement: Placement,
+  /// The floating content.
+  children: Children,
+) -> impl IntoView
+where
+  El: ElementDescriptor + Clone + 'static,
+{
+  let content = create_node_ref::<html::Div>();
+  let (position, set_position) = create_signal((0.0, 0.0));
+
+  let reposition = move || {
+    if let (Some(anchor), Some(content)) =
+      (anchor.get_untracked(), content.get_untracked())
+    {
+      let anchor = anchor.into_any().get_bounding_client_rect().into();
+      let content = content.into_any().get_bounding_client_rect().into();
+
+      set_position.set(placement.position(anchor, content));
+    }
+  };
+
+  // Position once both elements are mounted
+  create_effect({
+    let reposition = reposition.clone();
+
+    move |_| {
+      let _ = (anchor.get(), content.get());
+
+      reposition();
+    }
+  });
+
+  on_window_event("resize", reposition.clone());
+  on_window_event("scroll", reposition);
+
+  view! {
+    <div
+      node_ref=content
+      style:position="fixed"
+      style:left=move || format!("{}px", position.get().0)
+      style:top=move || format!("{}px", position.get().1)
+    >
+      {children()}
+    </div>
+  }
+}
+
+/// Calls `f` whenever `event` fires anywhere in the window, including scroll
+/// events of nested scrolling containers, until the current reactive owner is
+/// cleaned up.
+fn on_window_event(event: &'static str, f: impl Fn() + 'static) {
+  if !leptos_dom::is_browser() {
+    return;
+  }
+
+  let listener = Closure::<dyn Fn()>::new(f);
+
+  let options = web_sys::AddEventListenerOptions::new();
+  options.set_capture(true);
+  options.set_passive(true);
+
+  let _ = window()
+    .add_event_listener_with_callback_and_add_event_listener_options(
+      event,
+      listener.as_ref().unchecked_ref(),
+      &options,
+    );
+
+  on_cleanup(move || {
+    let _ = window().remove_event_listener_with_callback_and_bool(
+      event,
+      listener.as_ref().unchecked_ref(),
+      true,
+    );
+  });
+}
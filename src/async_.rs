@@ -1,10 +1,219 @@
-api_planning! {
-  view! { cx,
-    <Await>
-      <Ready>
-      </Ready>
-      <Pending>
-      </Pending>
-    </Await>
+//! Async views for [`leptos`].
+//!
+//! Please refer to [`AwaitView`] and [`WhenAllLoaded`] for usage examples.
+
+use leptos::*;
+use std::future::Future;
+
+/// Renders the view produced by an async function, showing `fallback` until
+/// it resolves.
+///
+/// This is particularly useful as the content of an [`If`](crate::if_::If)
+/// or [`Match`](crate::match_::Match) branch, as branches are only built the
+/// first time they are shown. Each branch therefore loads its own content,
+/// with its own loading state, only once it is switched to, rather than all
+/// of them being loaded up front behind a single [`Suspense`].
+///
+/// The future is run once, when [`AwaitView`] is rendered. Combine it with
+/// `keep_alive` on [`If`](crate::if_::If) to keep loaded branches around when
+/// switching away from them.
+///
+/// Note that this defers running the code of a branch, not downloading it:
+/// `wasm-bindgen` compiles the whole app into a single module, so there is no
+/// dynamic `import()` to split a branch's code into its own chunk. The
+/// future can however load whatever the branch needs at runtime, such as
+/// data or a separately built module fetched by the app.
+///
+/// Unlike leptos' own [`Await`](leptos::Await), the future produces the view
+/// itself, rather than data to render with `children`, and its output does
+/// not need to be serializable, as it is only run in the browser.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// async fn settings_page() -> impl IntoView {
+///   view! { <h1>"Settings"</h1> }
+/// }
+///
+/// async fn profile_page() -> impl IntoView {
+///   view! { <h1>"Profile"</h1> }
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (show_settings, _) = create_signal(true);
+///
+/// view! {
+/// <If signal=show_settings>
+///   <Then>
+///     <AwaitView future=settings_page fallback=|| "Loading settings..." />
+///   </Then>
+///   <Else>
+///     <AwaitView future=profile_page fallback=|| "Loading profile..." />
+///   </Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn AwaitView<F, Fut, V, FF, FV>(
+  /// The async function producing the view to render.
+  future: F,
+  /// What to show while `future` has not resolved yet.
+  fallback: FF,
+) -> impl IntoView
+where
+  F: Fn() -> Fut + 'static,
+  Fut: Future<Output = V> + 'static,
+  V: IntoView,
+  FF: Fn() -> FV + 'static,
+  FV: IntoView,
+{
+  let content = create_local_resource(
+    || (),
+    move |_| {
+      let content = future();
+
+      async move { content.await.into_view() }
+    },
+  );
+
+  view! {
+    <Suspense fallback=fallback>
+      {move || content.get()}
+    </Suspense>
+  }
+}
+
+/// Something which loads asynchronously, such as a [`Resource`].
+///
+/// Implemented for tuples, arrays and [`Vec`]s of loadables, which are loaded
+/// once all of their elements are.
+pub trait Loadable {
+  /// Whether this has finished loading. Reactive over the loading state.
+  fn is_loaded(&self) -> bool;
+}
+
+impl<S, T> Loadable for Resource<S, T>
+where
+  S: Clone + 'static,
+  T: 'static,
+{
+  fn is_loaded(&self) -> bool {
+    !self.loading().get() && self.with(Option::is_some)
+  }
+}
+
+impl<L: Loadable> Loadable for Vec<L> {
+  fn is_loaded(&self) -> bool {
+    self.iter().all(Loadable::is_loaded)
+  }
+}
+
+impl<L: Loadable, const N: usize> Loadable for [L; N] {
+  fn is_loaded(&self) -> bool {
+    self.iter().all(Loadable::is_loaded)
+  }
+}
+
+macro_rules! impl_loadable_for_tuples {
+  ($(($($l:ident),*)),*) => {
+    $(
+      impl<$($l: Loadable),*> Loadable for ($($l,)*) {
+        #[allow(non_snake_case)]
+        fn is_loaded(&self) -> bool {
+          let ($($l,)*) = self;
+
+          $($l.is_loaded())&&*
+        }
+      }
+    )*
+  };
+}
+
+impl_loadable_for_tuples!(
+  (A),
+  (A, B),
+  (A, B, C),
+  (A, B, C, D),
+  (A, B, C, D, E),
+  (A, B, C, D, E, F)
+);
+
+/// Returns a signal to whether `resource` resolved to an [`Err`], e.g. for the
+/// `error` prop of [`If`](crate::if_::If).
+///
+/// The signal is false while the resource has not resolved yet.
+pub fn resource_failed<S, T, E>(
+  resource: Resource<S, Result<T, E>>,
+) -> Signal<bool>
+where
+  S: Clone + 'static,
+  T: 'static,
+  E: 'static,
+{
+  Signal::derive(move || {
+    resource.with(|value| value.as_ref().is_some_and(Result::is_err))
+  })
+}
+
+/// Renders its children only once all of `resources` have loaded, showing
+/// `loading` until then.
+///
+/// Unlike nesting a [`Suspense`] per resource, the children are shown all at
+/// once, and only once every resource is ready. If any resource refetches,
+/// `loading` is shown again until it has loaded.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// async fn fetch_user() -> String {
+///   "Alice".to_string()
+/// }
+///
+/// async fn fetch_unread() -> usize {
+///   3
+/// }
+///
+/// # let runtime = create_runtime();
+/// # let local = tokio::task::LocalSet::new();
+/// # let _local = local.enter();
+/// let user = create_resource(|| (), |_| fetch_user());
+/// let unread = create_resource(|| (), |_| fetch_unread());
+///
+/// view! {
+/// <WhenAllLoaded resources=(user, unread) loading=|| "Loading...">
+///   <p>
+///     {move || user.get()} " has " {move || unread.get()} " unread messages."
+///   </p>
+/// </WhenAllLoaded>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn WhenAllLoaded<L, FL, IV>(
+  /// The resources to wait for, usually a tuple of [`Resource`]s.
+  resources: L,
+  /// What to show while any of `resources` is loading.
+  loading: FL,
+  /// What to show once all of `resources` have loaded.
+  children: Box<dyn Fn() -> Fragment>,
+) -> impl IntoView
+where
+  L: Loadable + 'static,
+  FL: Fn() -> IV + 'static,
+  IV: IntoView,
+{
+  let loaded = create_memo(move |_| resources.is_loaded());
+
+  move || {
+    if loaded.get() {
+      children().into_view()
+    } else {
+      loading().into_view()
+    }
   }
 }
@@ -0,0 +1,234 @@
+//! Portals across browser tabs for [`leptos`].
+//!
+//! Unlike [`portal`](crate::portal), these portals carry serializable data
+//! rather than views, as the data has to be sent through a
+//! [`BroadcastChannel`](web_sys::BroadcastChannel) to reach the other tabs.
+//!
+//! Please refer to [`BroadcastPortalInput`] for usage examples.
+
+use leptos::*;
+use serde::{
+  de::DeserializeOwned,
+  Serialize,
+};
+use std::any::type_name;
+use wasm_bindgen::{
+  closure::Closure,
+  JsCast,
+  JsValue,
+};
+
+/// Sends `data` to every [`BroadcastPortalOutput`] with the matching `id` in
+/// the other tabs and windows of the same origin.
+///
+/// The data is sent whenever it changes, and again whenever an output opens,
+/// e.g. in a tab opened later, so that the output does not stay empty until
+/// the data next changes. When this component unmounts, the outputs are
+/// emptied.
+///
+/// Nothing is sent where the page may not open a
+/// [`BroadcastChannel`](web_sys::BroadcastChannel), e.g. in a sandboxed iframe
+/// or from a `file://` URL, nor data which cannot be serialized to JSON, such
+/// as maps with keys which are not strings.
+///
+/// Nothing is sent outside the browser.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use serde::{
+///   Deserialize,
+///   Serialize,
+/// };
+///
+/// struct Notifications;
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Notification {
+///   message: String,
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (notification, _) = create_signal(Notification {
+///   message: "Saved!".to_string(),
+/// });
+///
+/// // In the tab sending notifications
+/// view! {
+///   <BroadcastPortalInput id=Notifications data=notification />
+/// };
+///
+/// // In every other tab
+/// view! {
+///   <BroadcastPortalOutput
+///     id=Notifications
+///     view=|notification: Notification| {
+///       view! { <p>{notification.message}</p> }.into_view()
+///     }
+///   />
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn BroadcastPortalInput<T, D>(
+  /// The type used as an `id`. This must match the `id` of the
+  /// corresponding [`BroadcastPortalOutput`].
+  ///
+  /// The name of the type identifies the channel, so both tabs must be
+  /// running the same build of the app.
+  id: T,
+  /// The data to send, sent again whenever it changes.
+  #[prop(into)]
+  data: Signal<D>,
+) -> impl IntoView
+where
+  T: 'static,
+  D: Serialize + Clone + 'static,
+{
+  let _ = id;
+
+  let Some(channel) = open_channel::<T>() else {
+    return;
+  };
+
+  create_effect({
+    let channel = channel.clone();
+
+    move |_| data.with(|data| post(&channel, Some(data)))
+  });
+
+  // Answers the outputs which open after the data was sent
+  let on_message = Closure::<dyn Fn(web_sys::MessageEvent)>::new({
+    let channel = channel.clone();
+
+    move |event: web_sys::MessageEvent| {
+      if event.data().as_string().as_deref() == Some(HELLO) {
+        data.with_untracked(|data| post(&channel, Some(data)));
+      }
+    }
+  });
+
+  channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+  on_cleanup(move || {
+    post(&channel, None::<&D>);
+
+    channel.set_onmessage(None);
+    channel.close();
+
+    drop(on_message);
+  });
+}
+
+/// Renders the data sent by the [`BroadcastPortalInput`] with the matching
+/// `id` in another tab or window, if any.
+///
+/// When it opens, it asks the inputs in the other tabs to send their data
+/// again, so that it renders the data sent before it opened, if the input is
+/// still mounted. Renders nothing until data is received, or once the input
+/// unmounts.
+#[component]
+pub fn BroadcastPortalOutput<T, D>(
+  /// The type used as an `id`. This must match the `id` of the
+  /// corresponding [`BroadcastPortalInput`].
+  id: T,
+  /// The view to render for the received data.
+  #[prop(into)]
+  view: Callback<D, View>,
+) -> impl IntoView
+where
+  T: 'static,
+  D: DeserializeOwned + Clone + 'static,
+{
+  let _ = id;
+
+  let (data, set_data) = create_signal(None::<D>);
+
+  if let Some(channel) = open_channel::<T>() {
+    let on_message = Closure::<dyn Fn(web_sys::MessageEvent)>::new(
+      move |event: web_sys::MessageEvent| {
+        let message = event.data().as_string().unwrap_or_default();
+
+        // Sent by the other outputs, for the inputs
+        if message == HELLO {
+          return;
+        }
+
+        match serde_json::from_str::<Option<D>>(&message) {
+          Ok(data) => set_data.set(data),
+          Err(_err) => {
+            #[cfg(debug_assertions)]
+            logging::warn!(
+              "`<BroadcastPortalOutput />` received data it could not \
+               deserialize, make sure both tabs run the same build: {_err}"
+            );
+          }
+        }
+      },
+    );
+
+    channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+    let _ = channel.post_message(&JsValue::from_str(HELLO));
+
+    on_cleanup(move || {
+      channel.set_onmessage(None);
+      channel.close();
+
+      drop(on_message);
+    });
+  }
+
+  move || data.get().map(|data| view.call(data))
+}
+
+/// The message a [`BroadcastPortalOutput`] sends when it opens, for the
+/// [`BroadcastPortalInput`]s to send their data again. It is not valid JSON,
+/// so it cannot be mistaken for data.
+const HELLO: &str = "hello";
+
+/// Opens the channel of the broadcast portal `T`, in the browser only, and
+/// only where the page may open one, e.g. not in a sandboxed iframe nor from
+/// a `file://` URL, whose origins are opaque.
+fn open_channel<T: 'static>() -> Option<web_sys::BroadcastChannel> {
+  if !leptos_dom::is_browser() {
+    return None;
+  }
+
+  let name = format!("leptos-declarative-portal:{}", type_name::<T>());
+
+  match web_sys::BroadcastChannel::new(&name) {
+    Ok(channel) => Some(channel),
+    Err(_err) => {
+      #[cfg(debug_assertions)]
+      logging::warn!(
+        "failed to open the `BroadcastChannel` of the broadcast portal \
+         `{}`, so it does nothing, which happens in sandboxed iframes and on \
+         `file://` pages: {_err:?}",
+        type_name::<T>()
+      );
+
+      None
+    }
+  }
+}
+
+/// Sends `data` to the other tabs, unless it cannot be serialized to JSON,
+/// e.g. maps with keys which are not strings.
+fn post<D: Serialize>(channel: &web_sys::BroadcastChannel, data: Option<&D>) {
+  let message = match serde_json::to_string(&data) {
+    Ok(message) => message,
+    Err(_err) => {
+      #[cfg(debug_assertions)]
+      logging::warn!(
+        "`<BroadcastPortalInput />` could not serialize its data to JSON, so \
+         it was not sent: {_err}"
+      );
+
+      return;
+    }
+  };
+
+  let _ = channel.post_message(&JsValue::from_str(&message));
+}
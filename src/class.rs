@@ -0,0 +1,68 @@
+//! Conditional classes for [`leptos`].
+//!
+//! Please refer to [`ConditionalClass`] for usage examples.
+
+use crate::condition::IntoCondition;
+use leptos::*;
+
+/// Adds `class` to its child element while a condition holds, and removes it
+/// otherwise.
+///
+/// Unlike an [`If`](crate::if_::If) with a branch per class, the child is
+/// built once and never swapped, only its class changes, so it keeps its
+/// state, e.g. focus or scroll position, and is cheaper to update.
+///
+/// The child must be a single element, otherwise it is rendered as is,
+/// without the class.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// fn render(selected: bool) -> String {
+///   leptos::ssr::render_to_string(move || {
+///     view! {
+///     <ConditionalClass class="selected" signal=selected>
+///       <li>"Inbox"</li>
+///     </ConditionalClass>
+///     }
+///   })
+///   .to_string()
+/// }
+///
+/// assert!(render(true).contains("selected"));
+/// assert!(!render(false).contains("selected"));
+/// ```
+#[component]
+pub fn ConditionalClass<C>(
+  /// The class to toggle.
+  class: &'static str,
+  /// The condition for the child to have `class`.
+  signal: C,
+  /// The element to toggle the class of.
+  children: Children,
+) -> impl IntoView
+where
+  C: IntoCondition + 'static,
+{
+  let signal = create_memo(move |_| signal.evaluate());
+
+  let children = children();
+
+  if let [View::Element(element)] = children.as_children() {
+    return element
+      .clone()
+      .into_html_element()
+      .class(class, signal)
+      .into_view();
+  }
+
+  #[cfg(debug_assertions)]
+  logging::warn!(
+    "`<ConditionalClass />` must have a single element as its child, the \
+     class `{class}` is not applied"
+  );
+
+  children.into_view()
+}
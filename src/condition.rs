@@ -0,0 +1,261 @@
+//! Conditions for [`If`](crate::if_::If) and [`ElseIf`](crate::if_::ElseIf).
+//!
+//! Please refer to [`IntoCondition`] for what counts as true, and to
+//! [`shared_condition!`](crate::shared_condition) for sharing a condition
+//! between several [`If`](crate::if_::If)s.
+
+#[cfg(not(feature = "nightly"))]
+use leptos::SignalWith;
+use leptos::{
+  create_memo,
+  provide_context,
+  store_value,
+  use_context,
+  Memo,
+  StoredValue,
+};
+
+/// A value which can be used as the condition of an
+/// [`If`](crate::if_::If) or [`ElseIf`](crate::if_::ElseIf).
+///
+/// [`bool`] is the canonical condition. The other implementations follow
+/// these truthiness rules:
+///
+/// - [`Option<T>`] is true when it is [`Some`], regardless of the value
+///   inside, so `Some(false)` and `Some(0)` are true.
+/// - Integers and floats are true when they are not zero. Floats which are
+///   `NaN` are true.
+/// - Signals of one of the above are true when their value is.
+/// - Anything callable with no arguments which returns one of the above, such
+///   as a closure, is true when its return value is. The condition is
+///   reactive over any signal read while calling it.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (unread, _) = create_signal(3);
+/// let (user, _) = create_signal(None::<String>);
+///
+/// view! {
+/// <If signal=move || unread.get()>
+///   <Then>"You have unread messages."</Then>
+///   <ElseIf signal=move || user.get()>"Nothing new."</ElseIf>
+///   <Else>"Please log in."</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+pub trait IntoCondition {
+  /// Evaluates whether the condition holds.
+  fn evaluate(&self) -> bool;
+}
+
+impl IntoCondition for bool {
+  fn evaluate(&self) -> bool {
+    *self
+  }
+}
+
+impl<T> IntoCondition for Option<T> {
+  fn evaluate(&self) -> bool {
+    self.is_some()
+  }
+}
+
+macro_rules! impl_into_condition_for_numbers {
+  ($zero:literal: $($ty:ty),*) => {
+    $(
+      impl IntoCondition for $ty {
+        fn evaluate(&self) -> bool {
+          *self != $zero
+        }
+      }
+    )*
+  };
+}
+
+impl_into_condition_for_numbers!(0: i8, i16, i32, i64, i128, isize);
+impl_into_condition_for_numbers!(0: u8, u16, u32, u64, u128, usize);
+impl_into_condition_for_numbers!(0.0: f32, f64);
+
+macro_rules! impl_into_condition_for_signals {
+  ($($signal:ident),*) => {
+    $(
+      // With leptos' `nightly` feature, signals are callable, and therefore
+      // already covered by the implementation for closures
+      #[cfg(not(feature = "nightly"))]
+      impl<T> IntoCondition for leptos::$signal<T>
+      where
+        T: IntoCondition + 'static,
+      {
+        fn evaluate(&self) -> bool {
+          self.with(IntoCondition::evaluate)
+        }
+      }
+    )*
+  };
+}
+
+impl_into_condition_for_signals!(
+  ReadSignal,
+  RwSignal,
+  Memo,
+  Signal,
+  MaybeSignal
+);
+
+impl<F, C> IntoCondition for F
+where
+  F: Fn() -> C,
+  C: IntoCondition,
+{
+  fn evaluate(&self) -> bool {
+    self().evaluate()
+  }
+}
+
+/// The shared conditions registered with [`provide_shared_condition`].
+#[derive(Clone, Copy)]
+struct SharedConditions(StoredValue<Vec<(&'static str, Memo<bool>)>>);
+
+/// Memoizes `condition` and registers it as the shared condition `key`, so
+/// that [`use_shared_condition`] can reuse it elsewhere rather than evaluate
+/// it again, and returns the memo.
+///
+/// Please refer to [`shared_condition!`](crate::shared_condition) for how
+/// keys are scoped.
+///
+/// # Panics
+/// In debug builds, if a condition is already registered as `key` in the
+/// same scope.
+pub fn provide_shared_condition<C>(
+  key: &'static str,
+  condition: C,
+) -> Memo<bool>
+where
+  C: IntoCondition + 'static,
+{
+  let conditions = use_context::<SharedConditions>().unwrap_or_else(|| {
+    let conditions = SharedConditions(store_value(Vec::new()));
+
+    provide_context(conditions);
+
+    conditions
+  });
+
+  #[cfg(debug_assertions)]
+  assert!(
+    !conditions.0.with_value(|conditions| conditions
+      .iter()
+      .any(|(other, _)| *other == key)),
+    "the shared condition `{key}` is registered twice in the same scope, \
+     give one of them another key"
+  );
+
+  let memo = create_memo(move |_| condition.evaluate());
+
+  conditions
+    .0
+    .update_value(|conditions| conditions.push((key, memo)));
+
+  memo
+}
+
+/// Returns the shared condition registered as `key` by
+/// [`provide_shared_condition`] in this scope.
+///
+/// # Panics
+/// If no condition is registered as `key` in this scope.
+pub fn use_shared_condition(key: &'static str) -> Memo<bool> {
+  use_context::<SharedConditions>()
+    .and_then(|conditions| {
+      conditions.0.with_value(|conditions| {
+        conditions
+          .iter()
+          .find(|(other, _)| *other == key)
+          .map(|(_, memo)| *memo)
+      })
+    })
+    .unwrap_or_else(|| {
+      panic!(
+        "no shared condition is registered as `{key}` here, register it \
+         with `shared_condition!(\"{key}\" => ...)` above this point"
+      )
+    })
+}
+
+/// Shares a condition between several [`If`](crate::if_::If)s, which would
+/// otherwise each evaluate it separately, by registering it under a key.
+///
+/// - `shared_condition!(key => condition)` memoizes `condition`, which can be
+///   anything implementing [`IntoCondition`], and registers the memo as
+///   `key`, with [`provide_shared_condition`].
+/// - `shared_condition!(key)` returns the memo registered as `key`, with
+///   [`use_shared_condition`].
+///
+/// Both return a [`Memo<bool>`], which can be given to any
+/// [`If`](crate::if_::If) as its `signal`, and is evaluated once however many
+/// of them use it.
+///
+/// # Scoping
+///
+/// Conditions are registered as context: the first registration provides a
+/// registry in the current reactive owner, and later ones, there or in any
+/// owner below it, add to that same registry. A key is therefore visible to
+/// the whole subtree below where the first condition was registered, but not
+/// to its siblings, so register shared conditions in a common ancestor of
+/// the [`If`](crate::if_::If)s using them, before rendering them.
+///
+/// Keys are plain strings, so two unrelated parts of the app could pick the
+/// same one. Registering a key twice in the same registry panics in debug
+/// builds. To avoid collisions, declare keys as constants next to the
+/// condition, and prefix them with the name of the component or feature
+/// they belong to, e.g. `"cart/has_items"`.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// const IS_ADMIN: &str = "account/is_admin";
+///
+/// #[component]
+/// fn Toolbar() -> impl IntoView {
+///   view! {
+///   <If signal=shared_condition!(IS_ADMIN)>
+///     <Then><button>"Delete"</button></Then>
+///   </If>
+///   }
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   let (roles, _) = create_signal(vec!["editor", "admin"]);
+///
+///   // Evaluated once, for both `If`s
+///   shared_condition!(IS_ADMIN => move || {
+///     roles.with(|roles| roles.contains(&"admin"))
+///   });
+///
+///   view! {
+///   <If signal=shared_condition!(IS_ADMIN)>
+///     <Then><h1>"Admin"</h1></Then>
+///   </If>
+///   <Toolbar />
+///   }
+/// });
+///
+/// assert!(html.contains("Admin"));
+/// assert!(html.contains("Delete"));
+/// ```
+#[macro_export]
+macro_rules! shared_condition {
+  ($key:expr => $condition:expr $(,)?) => {
+    $crate::condition::provide_shared_condition($key, $condition)
+  };
+  ($key:expr $(,)?) => {
+    $crate::condition::use_shared_condition($key)
+  };
+}
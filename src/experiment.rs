@@ -0,0 +1,167 @@
+//! Weighted branch selection for A/B experiments with [`leptos`].
+//!
+//! With [`IfStrategy::Weighted`](crate::if_::IfStrategy::Weighted) or
+//! [`MatchStrategy::Weighted`](crate::match_::MatchStrategy::Weighted),
+//! when several branches could be rendered, one of them is picked at random,
+//! in proportion to their `weight`, using the experiment seed in context.
+//!
+//! # Reproducibility
+//!
+//! The pick is not drawn from a stateful random number generator, but
+//! derived from the seed, the positions of the candidate branches and their
+//! weights. Therefore:
+//!
+//! - With the same seed, the same candidates always give the same branch,
+//!   however often the conditions are evaluated, so a user keeps seeing the
+//!   same variant for as long as the seed stays the same.
+//! - Across seeds, i.e. across users, each branch is picked in proportion to
+//!   its weight.
+//! - The pick is the same on the server and in the browser given the same
+//!   seed, so hydration finds the branch the server rendered.
+//!
+//! Without [`provide_experiment_seed`], the seed is drawn at random once per
+//! page load in the browser, and is `0` on the server. To keep variants
+//! across reloads, or to render them on the server, store a seed in the
+//! session, e.g. in a cookie, and provide it on both sides.
+//!
+//! Two experiments with the same candidates pick the same position under the
+//! same seed. To keep experiments independent, provide each with a seed of
+//! its own, e.g. derived from the session seed and the experiment name.
+//!
+//! # Examples
+//! ```rust
+//! use leptos::*;
+//! use leptos_declarative::prelude::*;
+//!
+//! let render = |seed| {
+//!   leptos::ssr::render_to_string(move || {
+//!     provide_experiment_seed(seed);
+//!
+//!     view! {
+//!     <If signal=false strategy=IfStrategy::Weighted>
+//!       <Then>"Signed in"</Then>
+//!       <ElseIf signal=true weight=3>"Sign up today!"</ElseIf>
+//!       <ElseIf signal=true weight=1>"Start your free trial!"</ElseIf>
+//!     </If>
+//!     }
+//!   })
+//!   .to_string()
+//! };
+//!
+//! // The same seed always renders the same variant
+//! assert_eq!(render(42), render(42));
+//!
+//! // Different seeds split between the variants by weight
+//! let sign_ups = (0..1000)
+//!   .filter(|seed| render(*seed).contains("Sign up today!"))
+//!   .count();
+//!
+//! assert!((650..850).contains(&sign_ups));
+//! ```
+
+use leptos::*;
+use std::cell::Cell;
+
+/// The seed provided with [`provide_experiment_seed`].
+#[derive(Clone, Copy)]
+struct ExperimentSeed(u64);
+
+thread_local! {
+  /// The seed used without [`provide_experiment_seed`], drawn once.
+  static DEFAULT_SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Provides the seed which weighted strategies pick branches with, to the
+/// current component and its children, please refer to the
+/// [module documentation](self).
+pub fn provide_experiment_seed(seed: u64) {
+  provide_context(ExperimentSeed(seed));
+}
+
+/// Returns the seed which weighted strategies pick branches with here, i.e.
+/// the closest one provided with [`provide_experiment_seed`], or else the
+/// default seed of the page.
+pub fn use_experiment_seed() -> u64 {
+  use_context::<ExperimentSeed>()
+    .map(|ExperimentSeed(seed)| seed)
+    .unwrap_or_else(default_seed)
+}
+
+/// Draws a random seed once per page load in the browser, and is `0` on the
+/// server.
+fn default_seed() -> u64 {
+  DEFAULT_SEED.with(|seed| {
+    if let Some(seed) = seed.get() {
+      return seed;
+    }
+
+    let drawn = random_seed().unwrap_or_default();
+
+    seed.set(Some(drawn));
+
+    drawn
+  })
+}
+
+fn random_seed() -> Option<u64> {
+  if !leptos_dom::is_browser() {
+    return None;
+  }
+
+  let mut bytes = [0; 8];
+
+  window()
+    .crypto()
+    .ok()?
+    .get_random_values_with_u8_array(&mut bytes)
+    .ok()?;
+
+  Some(u64::from_le_bytes(bytes))
+}
+
+/// Picks one of `candidates`, given as their position and weight, in
+/// proportion to their weights, deterministically from `seed`.
+///
+/// Candidates with a weight of `0` are never picked, unless all of them
+/// have one, in which case the first is.
+pub(crate) fn pick_weighted(
+  seed: u64,
+  candidates: &[(usize, u32)],
+) -> Option<usize> {
+  let total = candidates
+    .iter()
+    .map(|(_, weight)| u64::from(*weight))
+    .sum::<u64>();
+
+  if total == 0 {
+    return candidates.first().map(|(i, _)| *i);
+  }
+
+  let salt = candidates
+    .iter()
+    .fold(0, |salt, (i, _)| mix(salt ^ *i as u64));
+
+  let mut roll = mix(seed ^ salt) % total;
+
+  candidates.iter().find_map(|(i, weight)| {
+    let weight = u64::from(*weight);
+
+    if roll < weight {
+      Some(*i)
+    } else {
+      roll -= weight;
+
+      None
+    }
+  })
+}
+
+/// The SplitMix64 finalizer, which spreads similar inputs, such as
+/// consecutive seeds, over the whole range.
+fn mix(mut x: u64) -> u64 {
+  x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+  x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+  x ^ (x >> 31)
+}
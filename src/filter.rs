@@ -0,0 +1,81 @@
+//! Keyed list filtering for [`leptos`].
+//!
+//! Please refer to [`Filter`] for usage examples.
+
+use leptos::*;
+use std::{
+  hash::Hash,
+  rc::Rc,
+};
+
+/// Iterates over a keyed list like [`For`], but only renders the items for
+/// which `filter` returns `true`.
+///
+/// Every item stays in the underlying keyed list whether it is shown or not,
+/// and `filter` is evaluated reactively per item. Hiding or showing one item
+/// therefore only builds or removes that item's view, leaving its
+/// neighbours' DOM, state and animations untouched.
+///
+/// This differs from filtering the source collection inside `each`, where an
+/// item being filtered out removes it from the list entirely, causing it to
+/// be rebuilt from scratch, with fresh reactive state, when it comes back.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (todos, _) = create_signal(
+///   vec![(0, "Write docs", true), (1, "Write tests", false)],
+/// );
+/// let (hide_done, _) = create_signal(true);
+///
+/// view! {
+///   <ul>
+///     <Filter
+///       each=move || todos.get()
+///       key=|(id, _, _)| *id
+///       filter=move |(_, _, done)| !(hide_done.get() && *done)
+///       view=|(_, title, _)| view! { <li>{title}</li> }
+///     />
+///   </ul>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn Filter<IF, I, T, EF, N, KF, K, PF>(
+  /// Items over which the component should iterate.
+  each: IF,
+  /// A key function that will be applied to each item.
+  key: KF,
+  /// Whether an item should be shown.
+  filter: PF,
+  /// The view that will be displayed for each shown item.
+  view: EF,
+) -> impl IntoView
+where
+  IF: Fn() -> I + 'static,
+  I: IntoIterator<Item = T>,
+  EF: Fn(T) -> N + 'static,
+  N: IntoView,
+  KF: Fn(&T) -> K + 'static,
+  K: Eq + Hash + 'static,
+  T: Clone + 'static,
+  PF: Fn(&T) -> bool + 'static,
+{
+  let filter = Rc::new(filter);
+  let view = Rc::new(view);
+
+  let item_view = move |item: T| {
+    let filter = filter.clone();
+    let view = view.clone();
+
+    let item_for_filter = item.clone();
+    let show = create_memo(move |_| filter(&item_for_filter));
+
+    move || show.get().then(|| view(item.clone()))
+  };
+
+  view! { <For each=each key=key children=item_view /> }
+}
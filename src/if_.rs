@@ -2,12 +2,46 @@
 //!
 //! Please refer to [`If`] for usage examples.
 
+#[cfg(debug_assertions)]
+use crate::util::component_name;
+use crate::{
+  anchor::Rect,
+  condition::IntoCondition,
+  experiment::{
+    pick_weighted,
+    use_experiment_seed,
+  },
+  motion::{
+    TransitionPhase,
+    TransitionTimeline,
+  },
+  util::{
+    self,
+    collect_transparent,
+    observe_size,
+    MetricsRecorder,
+    TypedTransparent,
+  },
+};
 use leptos::*;
-use leptos_dom::Transparent;
+use leptos_dom::{
+  helpers::TimeoutHandle,
+  Transparent,
+};
+#[cfg(all(debug_assertions, feature = "strict-checks"))]
 use std::cell::Cell;
+use std::{
+  any::{
+    Any,
+    TypeId,
+  },
+  cmp::Reverse,
+  rc::Rc,
+  time::Duration,
+};
 
 api_planning! {
-  view! { cx,
+  view! {
     <If signal=bool_signal>
       <Then>
         "thing to show if bool_signal is true"
@@ -22,233 +56,2451 @@ api_planning! {
   }
 }
 
-/// The `if` construct in component form.
+/// The `if` construct in component form.
+///
+/// [`Then`] is the only required child component, as it's what will be shown
+/// when the [`If`]'s signal is true.
+///
+/// For more docs on allowed child components, check out [`IfProps::children`].
+///
+/// # Examples
+///
+/// ### Simple `if`
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (a, _) = create_signal(true);
+///
+/// view! {
+/// <If signal=a>
+///   <Then>"a is true!"</Then>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### `if/else`
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (a, _) = create_signal(true);
+///
+/// view! {
+/// <If signal=a>
+///   <Then>"A is true!"</Then>
+///   <Else>"A is false!"</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### `if/else-if`
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (a, _) = create_signal(true);
+/// let (b, _) = create_signal(false);
+///
+/// view! {
+/// <If signal=a>
+///   <Then>"A is true!"</Then>
+///   <ElseIf signal=b>"B is true!"</ElseIf>
+///   <Else>"Both A and B are false!"</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Conditions
+///
+/// The `signal` prop of the [`If`] and [`ElseIf`] component allow
+/// taking any value that implements [`IntoCondition`].
+/// This means that you can pass in [`bool`] values that are not
+/// strictly signals, such as in the following, as well as closures,
+/// [`Option`]s and numbers. Check out [`IntoCondition`] for the
+/// truthiness rules.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// view! {
+/// <If signal=true>
+///   <Then>"a is true!"</Then>
+///   <ElseIf signal=false>"b is true!"</ElseIf>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Server-side rendering
+///
+/// [`If`] renders the same on the server as it does in the browser. The
+/// [`Then`], [`ElseIf`] and [`Else`] blocks are collected from the children
+/// before anything is rendered, so choosing a branch never touches the DOM.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// fn render(a: bool, b: bool) -> String {
+///   leptos::ssr::render_to_string(move || {
+///     view! {
+///     <If signal=a>
+///       <Then>"A is true!"</Then>
+///       <ElseIf signal=b>"B is true!"</ElseIf>
+///       <Else>"Both A and B are false!"</Else>
+///     </If>
+///     }
+///   })
+///   .to_string()
+/// }
+///
+/// let html = render(true, false);
+/// assert!(html.contains("A is true!"));
+/// assert!(!html.contains("B is true!"));
+///
+/// let html = render(false, true);
+/// assert!(html.contains("B is true!"));
+/// assert!(!html.contains("Both A and B are false!"));
+///
+/// let html = render(false, false);
+/// assert!(html.contains("Both A and B are false!"));
+/// assert!(!html.contains("A is true!"));
+/// ```
+///
+/// ### Async branches
+///
+/// Branches are rendered lazily, the first time they are shown, so a branch
+/// can load its own data and show its own fallback with [`Suspense`]
+/// without wrapping the whole [`If`] in one. The resource below is only
+/// created, and therefore only fetched, once `show` is true.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// async fn fetch_user() -> String {
+///   "Alice".to_string()
+/// }
+///
+/// # let runtime = create_runtime();
+/// # let local = tokio::task::LocalSet::new();
+/// # let _local = local.enter();
+/// let (show, _) = create_signal(true);
+///
+/// view! {
+/// <If signal=show>
+///   <Then>
+///     {
+///       let user = create_resource(|| (), |_| fetch_user());
+///
+///       view! {
+///         <Suspense fallback=|| "Loading...">
+///           {move || user.get().map(|user| view! { <p>{user}</p> })}
+///         </Suspense>
+///       }
+///     }
+///   </Then>
+///   <Else>"Nothing to show."</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// Branches are built under the reactive owner of the [`If`], even when
+/// built lazily, or kept alive, so resources read by a branch without a
+/// [`Suspense`] of its own register with the [`Suspense`] or [`Transition`]
+/// around the [`If`], whose fallback then waits for them too. The same goes
+/// for the arms of a [`Match`](crate::match_::Match).
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// async fn fetch_user() -> String {
+///   "Alice".to_string()
+/// }
+///
+/// #[component]
+/// fn User() -> impl IntoView {
+///   let user = create_resource(|| (), |_| fetch_user());
+///
+///   move || user.get().map(|user| view! { <p>{user}</p> })
+/// }
+///
+/// fn is_suspended(content: fn() -> View) -> bool {
+///   leptos::ssr::render_to_string(move || {
+///     view! {
+///     <Suspense fallback=|| "Loading...">
+///       {content()}
+///     </Suspense>
+///     }
+///   })
+///   .contains("Loading...")
+/// }
+///
+/// # let local = tokio::task::LocalSet::new();
+/// # let _local = local.enter();
+/// assert!(is_suspended(|| view! {
+///   <If signal=true>
+///     <Then><User /></Then>
+///   </If>
+/// }
+/// .into_view()));
+/// assert!(is_suspended(|| view! {
+///   <If signal=true keep_alive=true>
+///     <Then><User /></Then>
+///   </If>
+/// }
+/// .into_view()));
+/// assert!(is_suspended(|| view! {
+///   <Match signal=1>
+///     <Arm value=1><User /></Arm>
+///   </Match>
+/// }
+/// .into_view()));
+///
+/// // Branches without resources do not suspend
+/// assert!(!is_suspended(|| view! {
+///   <If signal=true>
+///     <Then>"Alice"</Then>
+///   </If>
+/// }
+/// .into_view()));
+/// ```
+///
+/// ### List-shaped branches
+///
+/// A branch can render any number of root nodes, including a number only
+/// known at runtime. Anything implementing [`IntoView`] can be used, such as
+/// a `Vec<View>`, or an iterator of views collected with
+/// [`CollectView::collect_view`].
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// fn render(count: usize) -> String {
+///   leptos::ssr::render_to_string(move || {
+///     view! {
+///     <ul>
+///       <If signal={count != 0}>
+///         <Then>
+///           {(0..count).map(|i| view! { <li>{i}</li> }).collect_view()}
+///         </Then>
+///         <Else>"Nothing here."</Else>
+///       </If>
+///     </ul>
+///     }
+///   })
+///   .to_string()
+/// }
+///
+/// assert_eq!(render(3).matches("<li").count(), 3);
+/// assert_eq!(render(5).matches("<li").count(), 5);
+/// assert!(render(0).contains("Nothing here."));
+/// ```
+///
+/// ### Keeping branches alive
+///
+/// By default, a branch is unmounted as soon as another one is shown. With
+/// `keep_alive`, branches stay mounted once built and are hidden instead,
+/// and `preload` builds the listed branches up front.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (show_settings, _) = create_signal(false);
+///
+/// view! {
+/// <If signal=show_settings keep_alive=true preload=vec![0]>
+///   <Then>"Settings tab"</Then>
+///   <Else>"Profile tab"</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// A branch which loads its own data behind a [`Suspense`] therefore only
+/// loads it once: switching away from it and back shows the data already
+/// loaded, rather than creating the resource again.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::sync::atomic::{
+///   AtomicUsize,
+///   Ordering,
+/// };
+///
+/// static FETCHES: AtomicUsize = AtomicUsize::new(0);
+///
+/// async fn fetch_user() -> String {
+///   "Alice".to_string()
+/// }
+///
+/// #[component]
+/// fn User() -> impl IntoView {
+///   let user = create_resource(
+///     || (),
+///     |_| {
+///       FETCHES.fetch_add(1, Ordering::Relaxed);
+///
+///       fetch_user()
+///     },
+///   );
+///
+///   view! {
+///     <Suspense fallback=|| "Loading...">
+///       {move || user.get().map(|user| view! { <p>{user}</p> })}
+///     </Suspense>
+///   }
+/// }
+///
+/// # let runtime = create_runtime();
+/// # let local = tokio::task::LocalSet::new();
+/// # let _local = local.enter();
+/// let (show_user, set_show_user) = create_signal(false);
+///
+/// let _view = view! {
+/// <If signal=show_user keep_alive=true>
+///   <Then><User /></Then>
+///   <Else>"Nothing to show."</Else>
+/// </If>
+/// };
+///
+/// // Built, and fetched, the first time it is shown
+/// set_show_user.set(true);
+/// assert_eq!(FETCHES.load(Ordering::Relaxed), 1);
+///
+/// // Kept while hidden, so showing it again does not fetch again
+/// set_show_user.set(false);
+/// set_show_user.set(true);
+/// assert_eq!(FETCHES.load(Ordering::Relaxed), 1);
+/// # runtime.dispose();
+/// ```
+///
+/// ### Persisting branch state
+///
+/// `keep_alive` keeps hidden branches mounted, DOM, effects and all. When a
+/// branch only needs its data back, e.g. the result of a costly computation
+/// or user input, `persist_state` is lighter: branches unmount as usual, but
+/// the state they create with [`use_branch_state`] is kept, and given back to
+/// them when they are shown again, so they rebuild their DOM from it.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// fn summarize(rows: &[u32]) -> u32 {
+///   rows.iter().sum()
+/// }
+///
+/// #[component]
+/// fn Report() -> impl IntoView {
+///   // Only computed the first time the branch is shown
+///   let total = use_branch_state(|| summarize(&[1, 2, 3]));
+///
+///   view! { <p>"Total: " {total.get_value()}</p> }
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (show_report, _) = create_signal(true);
+///
+/// view! {
+/// <If signal=show_report persist_state=true>
+///   <Then><Report /></Then>
+///   <Else>"Report hidden."</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Wrapping branches
+///
+/// Giving a branch attributes with the `attr:` prefix wraps its content in a
+/// `<div>` with those attributes, which is handy for styling each branch, or
+/// giving transitions a stable element to target.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=true>
+///     <Then attr:class="branch branch-then">"A is true!"</Then>
+///     <Else>"A is false!"</Else>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("branch-then"));
+/// ```
+///
+/// ### Wrapping the `If`
+///
+/// By default, [`If`] renders no element of its own. With `wrapper`, it
+/// renders the branches inside an element with that tag, given the
+/// attributes set on [`If`] with the `attr:` prefix, so that there is a
+/// single, stable element around whichever branch is shown, e.g. to lay it
+/// out in a grid, or to measure it.
+///
+/// Giving the wrapper `display: contents` removes its box, so that the
+/// content of the branches takes part in the layout of the parent as if
+/// there was no wrapper, while the element still exists, e.g. for event
+/// delegation or selectors. An element without a box has no size nor
+/// position, so it can then neither be measured nor scrolled to, and
+/// transforms and transitions on the wrapper itself have no visible effect.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=true wrapper="section" attr:class="panel">
+///     <Then>"A is true!"</Then>
+///     <Else>"A is false!"</Else>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("<section"));
+/// assert!(html.contains("panel"));
+/// ```
+///
+/// ### Prioritized branches
+///
+/// With `strategy=IfStrategy::HighestPriority`, the true [`ElseIf`] with the
+/// highest `priority` is rendered, wherever it is declared.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=false strategy=IfStrategy::HighestPriority>
+///     <Then>"Nothing to report."</Then>
+///     <ElseIf signal=true>"You have unread messages."</ElseIf>
+///     <ElseIf signal=true priority=10>"Your session is about to expire!"</ElseIf>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("Your session is about to expire!"));
+/// assert!(!html.contains("You have unread messages."));
+/// ```
+///
+/// ### Weighted branches
+///
+/// With `strategy=IfStrategy::Weighted`, one of the true [`ElseIf`]s is
+/// picked at random, in proportion to its `weight`, e.g. to render the
+/// variants of an A/B experiment. The pick is stable for a given seed,
+/// please refer to the [`experiment`](crate::experiment) module.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   // Usually stored in the session
+///   provide_experiment_seed(7);
+///
+///   view! {
+///   <If signal=false strategy=IfStrategy::Weighted>
+///     <Then>"Signed in"</Then>
+///     <ElseIf signal=true weight=9>"Sign up today!"</ElseIf>
+///     <ElseIf signal=true weight=1>"Start your free trial!"</ElseIf>
+///   </If>
+///   }
+/// });
+///
+/// assert!(
+///   html.contains("Sign up today!") || html.contains("Start your free trial!")
+/// );
+/// ```
+///
+/// ### Context in branches
+///
+/// Each branch is rendered under a reactive owner of its own, so context
+/// provided within a branch is only visible to that branch, and is removed
+/// when the branch unmounts. This holds with `keep_alive` too, where hidden
+/// branches stay mounted alongside the active one.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Editing;
+///
+/// #[component]
+/// fn Editor() -> impl IntoView {
+///   provide_context(Editing);
+///
+///   "Editing"
+/// }
+///
+/// #[component]
+/// fn Preview() -> impl IntoView {
+///   if use_context::<Editing>().is_some() {
+///     "Leaked from the editor"
+///   } else {
+///     "Previewing"
+///   }
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=true keep_alive=true preload=vec![0, 1]>
+///     <Then><Editor /></Then>
+///     <Else><Preview /></Else>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("Previewing"));
+/// assert!(!html.contains("Leaked from the editor"));
+/// ```
+///
+/// ### Style isolation
+///
+/// With `shadow_host`, the branches are rendered inside the shadow root of a
+/// custom element, so that the styles of the page do not leak into them and
+/// theirs do not leak out, which is useful for micro-frontends and design
+/// systems.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=true shadow_host="settings-panel">
+///     <Then>"Settings"</Then>
+///     <Else>"Profile"</Else>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("<settings-panel"));
+/// ```
+///
+/// ### Hydration
+///
+/// The condition must evaluate the same on the server and during hydration,
+/// or the DOM the browser expects differs from the one the server sent. Read
+/// browser-only state, or the time, in an effect, which only runs in the
+/// browser, after hydration.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   let morning = create_rw_signal(false);
+///
+///   create_effect(move |_| morning.set(true));
+///
+///   view! {
+///   <If signal=morning>
+///     <Then>"Good morning!"</Then>
+///     <Else>"Good evening!"</Else>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("Good evening!"));
+/// ```
+///
+/// ### Revalidating conditions
+///
+/// Conditions computed from async data can change meaning while the data is
+/// refetched. With `stale_while_revalidate`, the branch shown before keeps
+/// being shown until the data has loaded again.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// async fn fetch_unread(user_id: u32) -> usize {
+///   user_id as usize
+/// }
+///
+/// # let runtime = create_runtime();
+/// # let local = tokio::task::LocalSet::new();
+/// # let _local = local.enter();
+/// let (user_id, _) = create_signal(0);
+/// let unread = create_resource(move || user_id.get(), fetch_unread);
+///
+/// view! {
+/// <If
+///   signal=move || unread.get().unwrap_or_default()
+///   stale_while_revalidate=unread.loading()
+/// >
+///   <Then>"You have unread messages."</Then>
+///   <Else>"Nothing new."</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Failed conditions
+///
+/// A condition computed from data which failed to load is neither true nor
+/// false. With `error`, an [`OnError`] branch is shown instead while it is
+/// true, rather than the condition falling back to false and the `else`
+/// branch silently being shown.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// async fn fetch_unread() -> Result<usize, String> {
+///   Err("offline".to_string())
+/// }
+///
+/// # let runtime = create_runtime();
+/// # let local = tokio::task::LocalSet::new();
+/// # let _local = local.enter();
+/// let unread = create_resource(|| (), |_| fetch_unread());
+///
+/// view! {
+/// <If
+///   signal=move || unread.get().and_then(Result::ok).unwrap_or_default()
+///   error=resource_failed(unread)
+/// >
+///   <Then>"You have unread messages."</Then>
+///   <OnError>"Could not check your messages."</OnError>
+///   <Else>"Nothing new."</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Updating several conditions at once
+///
+/// The branch is selected by a single memo reading every condition, so when
+/// several of them change inside a [`batch`], the branch is selected once,
+/// after all of them have changed, and no branch is built in between. Outside
+/// of a [`batch`], each change selects a branch of its own, which may build
+/// a branch that is replaced right away.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (logged_in, set_logged_in) = create_signal(false);
+/// let (is_admin, set_is_admin) = create_signal(false);
+///
+/// view! {
+/// <If signal=move || logged_in.get() && is_admin.get()>
+///   <Then>"Admin dashboard"</Then>
+///   <ElseIf signal=logged_in>"Dashboard"</ElseIf>
+///   <Else>"Please log in."</Else>
+/// </If>
+/// };
+///
+/// // Shows the admin dashboard without showing the dashboard first
+/// batch(|| {
+///   set_logged_in.set(true);
+///   set_is_admin.set(true);
+/// });
+/// # runtime.dispose();
+/// ```
+///
+/// ### Branch lifecycle
+///
+/// `on_branch_enter` and `on_branch_leave` are called with the [`BranchId`]
+/// of a branch once it is shown and once it is not anymore, for imperative
+/// setup and teardown, e.g. of a third-party widget. Unlike `transition`,
+/// they run whether or not motion is allowed, and only in the browser.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (show_map, _) = create_signal(true);
+///
+/// view! {
+/// <If
+///   signal=show_map
+///   on_branch_enter=|branch| logging::log!("{branch:?} entered")
+///   on_branch_leave=|branch| logging::log!("{branch:?} left")
+/// >
+///   <Then key="map"><div id="map" /></Then>
+///   <Else>"The map is hidden."</Else>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Fallback
+///
+/// Without an [`Else`], nothing is shown when all conditions are false, unless
+/// `fallback` is given. An [`If`] can have either an [`Else`] or a
+/// `fallback`, but not both, which panics in debug builds.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=false fallback=|| "Nothing to show.">
+///     <Then>"Something to show!"</Then>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("Nothing to show."));
+/// ```
+///
+/// ```rust,should_panic
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=false fallback=|| "Nothing to show.">
+///     <Then>"Something to show!"</Then>
+///     <Else>"Something else to show!"</Else>
+///   </If>
+///   }
+/// });
+/// ```
+///
+/// ### Reserving space
+///
+/// Content appearing or disappearing shifts whatever comes after it. With
+/// `reserve_space`, an invisible spacer of the given minimum height is
+/// rendered while no branch is shown, so that a branch appearing later does
+/// not move the rest of the page. [`PortalOutput`](crate::portal::PortalOutput)
+/// has the same prop.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=false reserve_space="120px">
+///     <Then>"Recommendations"</Then>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("min-height: 120px"));
+/// ```
+///
+/// ### Scrolling into view
+///
+/// With `scroll_into_view`, a branch which is shown later on is scrolled to,
+/// e.g. an error message appearing at the bottom of a long form.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (error, _) = create_signal(None::<String>);
+///
+/// view! {
+/// <If
+///   signal=move || error.get()
+///   scroll_into_view=true
+///   scroll_options=ScrollOptions {
+///     behavior: ScrollBehavior::Smooth,
+///     block: ScrollAlignment::Center,
+///   }
+/// >
+///   <Then>
+///     <p class="error">{move || error.get()}</p>
+///   </Then>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Measuring all branches
+///
+/// With `measure_all`, every branch is mounted at once, with the inactive
+/// ones laid out but hidden, so that they can all be measured, e.g. to give
+/// the container the height of its tallest branch.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (expanded, _) = create_signal(false);
+/// let (min_height, set_min_height) = create_signal(0.0_f64);
+///
+/// view! {
+/// <div
+///   style="position: relative"
+///   style:min-height=move || format!("{}px", min_height.get())
+/// >
+///   <If
+///     signal=expanded
+///     measure_all=true
+///     on_measure=move |(_, rect): (BranchId, Rect)| {
+///       set_min_height.update(|height| *height = height.max(rect.height))
+///     }
+///   >
+///     <Then>"All the details"</Then>
+///     <Else>"A summary"</Else>
+///   </If>
+/// </div>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// Inactive branches are in the DOM, hidden:
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=false measure_all=true>
+///     <Then>"All the details"</Then>
+///     <Else>"A summary"</Else>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("All the details"));
+/// assert!(html.contains("A summary"));
+/// assert!(html.contains("position: absolute; visibility: hidden"));
+/// ```
+///
+/// ### DOM structure
+///
+/// By default, [`If`] inserts no element of its own: the content of the
+/// active branch is placed directly in the parent, between the comment or
+/// empty text nodes leptos uses to delimit dynamic content. These are not
+/// elements, so the content takes part in the layout of the parent, e.g. as
+/// grid or flex items, exactly as if it was written there.
+///
+/// Some props do wrap the branches in an element, which then becomes the
+/// grid or flex item instead of the content:
+///
+/// - `keep_alive` wraps each built branch in a `<div>`, which has
+///   `display: contents` while the branch is active, so the content still
+///   takes part in the layout of the parent.
+/// - `measure_all` wraps each branch in a `<div>`, which is what gets
+///   measured, so it stays a box of its own even while active.
+/// - Attributes set on a branch with the `attr:` prefix wrap its content in a
+///   `<div>` with those attributes. Add `attr:style="display: contents"` to
+///   make it transparent to the layout.
+/// - `transition` wraps the branches in a `<div>` with the transition
+///   classes, unless there is a `wrapper` to set them on.
+/// - `wrapper`, or attributes set on [`If`] itself with the `attr:` prefix,
+///   wrap the branches in an element of their own, please refer to
+///   [Wrapping the `If`](#wrapping-the-if).
+/// - `shadow_host` wraps the branches in the custom element hosting the
+///   shadow root.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <div style="display: grid; grid-template-columns: 1fr 1fr">
+///     <If signal=true>
+///       <Then>
+///         <aside>"Sidebar"</aside>
+///         <main>"Content"</main>
+///       </Then>
+///     </If>
+///   </div>
+///   }
+/// });
+///
+/// // `<aside>` and `<main>` are both grid items
+/// assert!(html.contains("<aside"));
+/// assert!(!html.contains("<div style=\"display: contents\""));
+/// ```
+#[component]
+pub fn If<C>(
+  /// The condition.
+  signal: C,
+  /// The `if` conditions you would like to evaluate.
+  ///
+  /// Children must be any
+  /// - [`Then`]
+  /// - [`ElseIf`]
+  /// - [`Else`]
+  /// - [`ElseWith`]
+  /// - [`OnError`]
+  ///
+  /// Any other child not in the above list will not be rendered.
+  ///
+  /// [`Then`] must be present and the first child.
+  ///
+  /// [`Else`] or [`ElseWith`] must be the last child.
+  children: Box<dyn Fn() -> Fragment>,
+  /// Keeps branches mounted once they have been shown, hiding inactive ones
+  /// with `display: none` instead of unmounting them, so switching back to
+  /// them is instant and preserves their state.
+  ///
+  /// Each branch is wrapped in a `<div>` which has `display: contents` while
+  /// the branch is active.
+  #[prop(optional)]
+  keep_alive: bool,
+  /// With `keep_alive`, the branches to build when [`If`] is first rendered,
+  /// rather than the first time they are shown, so that their first
+  /// activation is instant.
+  ///
+  /// Branches are identified by their position among the children, with
+  /// [`Then`] being `0`.
+  ///
+  /// Preloaded branches are built and kept in memory even if they are never
+  /// shown, so only preload branches which are likely to be shown soon.
+  #[prop(optional)]
+  preload: Vec<usize>,
+  /// Keeps the state branches create with [`use_branch_state`] when they
+  /// unmount, and hands it back to them when they are shown again, so that
+  /// they can rebuild their DOM from it rather than start over.
+  ///
+  /// Unlike `keep_alive`, which keeps the whole branch mounted, only the
+  /// state is kept, so hidden branches cost no DOM, and their effects do not
+  /// run. Has no effect with `keep_alive` or `measure_all`, where branches
+  /// stay mounted, and their state with them.
+  #[prop(optional)]
+  persist_state: bool,
+  /// Mounts every branch when [`If`] is first rendered, and keeps them all
+  /// mounted, so that the inactive ones can be measured, e.g. to size a
+  /// container to the largest branch so that it does not resize when
+  /// switching branches.
+  ///
+  /// Each branch is wrapped in a `<div>`. Inactive ones have
+  /// `position: absolute; visibility: hidden`, so they are laid out but
+  /// neither shown nor taking up space, and only the active one is visible.
+  /// Give the parent `position: relative` to keep the hidden branches within
+  /// it.
+  ///
+  /// Unlike `keep_alive`, which builds branches the first time they are
+  /// shown, all branches are built up front. This takes precedence over
+  /// `keep_alive`.
+  #[prop(optional)]
+  measure_all: bool,
+  /// With `measure_all`, called with a branch and the bounding rect of its
+  /// `<div>` when it is first rendered and whenever its size changes, in the
+  /// browser only.
+  #[prop(optional, into)]
+  on_measure: Option<Callback<(BranchId, Rect)>>,
+  /// How the [`ElseIf`] to render is chosen when several of them are true.
+  #[prop(optional)]
+  strategy: IfStrategy,
+  /// Renders the branches inside a custom element with the given tag, e.g.
+  /// `"settings-panel"`, whose shadow root isolates them from the styles of
+  /// the rest of the page, and the rest of the page from theirs.
+  ///
+  /// The tag must be a valid custom element name, i.e. contain a `-`. The
+  /// element does not need to be defined with `customElements.define()`.
+  ///
+  /// Shadow roots only exist in the browser, so on the server the branches
+  /// are rendered directly inside the element, without isolation.
+  #[prop(optional)]
+  shadow_host: Option<&'static str>,
+  /// Transitions between branches with CSS classes derived from the given
+  /// name, e.g. `"fade"`, following the [`TransitionTimeline`] in context, or
+  /// a default one of its own:
+  ///
+  /// - The branches are wrapped in a `<div>` with the `fade` class.
+  /// - While the old branch leaves, the `<div>` also has the `fade-leave`
+  ///   class.
+  /// - While the new branch enters, it has the `fade-enter` class instead.
+  ///
+  /// Branches are swapped immediately when motion is not allowed, please
+  /// refer to [`use_motion_allowed`](crate::motion::use_motion_allowed).
+  ///
+  /// Branches are told apart by their `key`, or by their position when they
+  /// have none. Switching between branches with the same `key` swaps them
+  /// without a transition, e.g. when two conditions show the same content.
+  #[prop(optional)]
+  transition: Option<&'static str>,
+  /// Runs the `transition` even when the user has asked for reduced motion,
+  /// for transitions which convey meaning, please refer to
+  /// [`use_motion_allowed`](crate::motion::use_motion_allowed).
+  ///
+  /// A [`TransitionTimeline`] in context with a `force_motion` of its own
+  /// runs for every [`If`] following it.
+  #[prop(optional, into)]
+  force_motion: MaybeSignal<bool>,
+  /// While this is true, keeps showing the branch shown before, rather than
+  /// switching branches as the conditions change, and switches once it is
+  /// false again.
+  ///
+  /// This is usually the loading state of the data the conditions depend
+  /// on, e.g. [`Resource::loading`], so that the branch does not flicker
+  /// while the data is refetched.
+  #[prop(optional, into)]
+  stale_while_revalidate: Option<Signal<bool>>,
+  /// While this is true, shows the [`OnError`] branch instead of any other,
+  /// or no branch if there is none, whatever the conditions.
+  ///
+  /// This is usually whether the data the conditions depend on failed to
+  /// load, e.g. [`resource_failed`](crate::async_::resource_failed), so that
+  /// a failure is not mistaken for a false condition.
+  #[prop(optional, into)]
+  error: Option<Signal<bool>>,
+  /// Only switches branches once the conditions have selected the same new
+  /// branch for this many milliseconds, so that conditions flipping back and
+  /// forth quickly, e.g. from a noisy sensor, do not make the branches
+  /// flicker. Flipping back before then restarts the wait.
+  ///
+  /// Branches switch immediately on the server, where there is no time to
+  /// wait for.
+  #[prop(optional)]
+  min_stable_ms: Option<u64>,
+  /// Reserves at least this height, any CSS length such as `"120px"`, with an
+  /// invisible spacer while no branch is shown, so that a branch appearing
+  /// later does not shift the layout.
+  #[prop(optional)]
+  reserve_space: Option<&'static str>,
+  /// What to show when no branch is, i.e. when all conditions are false and
+  /// there is no [`Else`].
+  ///
+  /// An [`Else`] is shown whenever no other branch is, so this would never
+  /// be shown alongside one: giving both panics in debug builds, and the
+  /// [`Else`] wins in release builds.
+  #[prop(optional, into)]
+  fallback: Option<ViewFn>,
+  /// Called with the branch which was just shown, once it is mounted, in the
+  /// browser only, e.g. to set up a third-party widget inside it.
+  ///
+  /// With `keep_alive`, this is called whenever a branch is shown again, not
+  /// only when it is first built.
+  #[prop(optional, into)]
+  on_branch_enter: Option<Callback<BranchId>>,
+  /// Called with the branch which was shown until now, when another branch is
+  /// shown or when the [`If`] unmounts, in the browser only, e.g. to tear
+  /// down what `on_branch_enter` set up.
+  ///
+  /// With `keep_alive`, a branch which is left is hidden rather than
+  /// unmounted, so its DOM is still there when this is called.
+  #[prop(optional, into)]
+  on_branch_leave: Option<Callback<BranchId>>,
+  /// Scrolls the first element of a branch into view whenever it is shown
+  /// instead of another branch, or instead of nothing, e.g. so that an error
+  /// message appearing below the fold is seen. The branch shown when [`If`]
+  /// is first rendered is not scrolled to.
+  ///
+  /// Only in the browser, and only for branches with an element.
+  #[prop(optional)]
+  scroll_into_view: bool,
+  /// How `scroll_into_view` scrolls.
+  #[prop(optional)]
+  scroll_options: ScrollOptions,
+  /// Renders the branches inside an element with this tag, e.g.
+  /// `"section"`, which is given the attributes set on [`If`] with the
+  /// `attr:` prefix. Attributes alone wrap the branches in a `<div>`.
+  ///
+  /// With `transition`, the transition classes are set on this element,
+  /// unless it is given a `class` of its own, in which case the branches
+  /// are wrapped in a second `<div>` for them.
+  #[prop(optional)]
+  wrapper: Option<&'static str>,
+  /// The attributes of the `wrapper` element.
+  #[prop(attrs)]
+  attrs: Vec<(&'static str, Attribute)>,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
+) -> impl IntoView
+where
+  C: IntoCondition + 'static,
+{
+  // Memoize the signal
+  let signal = create_memo(move |_| signal.evaluate());
+
+  let children = children();
+
+  // A lone `<Then />` is by far the most common case, where there is nothing
+  // to select between, so unless a prop needs them, the branch is rendered
+  // without collecting the blocks nor any of the bookkeeping below
+  let needs_blocks = keep_alive
+    || !preload.is_empty()
+    || persist_state
+    || measure_all
+    || on_measure.is_some()
+    || strategy != IfStrategy::default()
+    || shadow_host.is_some()
+    || transition.is_some()
+    || stale_while_revalidate.is_some()
+    || error.is_some()
+    || min_stable_ms.is_some()
+    || reserve_space.is_some()
+    || fallback.is_some()
+    || on_branch_enter.is_some()
+    || on_branch_leave.is_some()
+    || scroll_into_view
+    || wrapper.is_some()
+    || !attrs.is_empty();
+
+  if !needs_blocks {
+    if let Some(then) = lone_then(&children) {
+      #[cfg(all(debug_assertions, feature = "strict-checks"))]
+      warn_on_constant_condition(signal, debug_name);
+
+      return render_lone_then(
+        signal,
+        then,
+        MetricsRecorder::new("If", debug_name),
+      );
+    }
+  }
+
+  // Get the condition blocks
+  let if_blocks = collect_transparent::<IfBlock>(&children)
+    .into_iter()
+    .collect::<Rc<[_]>>();
+
+  #[cfg(debug_assertions)]
+  run_debug_checks(&if_blocks, fallback.is_some(), debug_name);
+  #[cfg(all(debug_assertions, feature = "strict-checks"))]
+  warn_on_constant_condition(signal, debug_name);
+  #[cfg(not(debug_assertions))]
+  let _ = debug_name;
+
+  let metrics = MetricsRecorder::new("If", debug_name);
+
+  let on_error = if_blocks.iter().position(|block| block.is_on_error());
+
+  #[cfg(debug_assertions)]
+  if error.is_some() && on_error.is_none() {
+    logging::warn!(
+      "{} has an `error` signal but no `<OnError />` child, so no branch is \
+       shown on errors",
+      component_name("If", debug_name)
+    );
+  }
+
+  // Every condition is read by this one memo, so that conditions changing
+  // together in a `batch` select a branch only once
+  let active_block = create_memo({
+    let if_blocks = if_blocks.clone();
+    let metrics = metrics.clone();
+
+    move |_| {
+      metrics.evaluation();
+
+      if error.is_some_and(|error| error.get()) {
+        return on_error;
+      }
+
+      select_block(&if_blocks, strategy, |_, block| {
+        if block.is_if() {
+          signal.get()
+        } else {
+          block.is_true()
+        }
+      })
+    }
+  });
+
+  let active_block = if let Some(pending) = stale_while_revalidate {
+    hold_while_pending(active_block, pending)
+  } else {
+    active_block
+  };
+
+  let active_block = if let Some(min_stable_ms) = min_stable_ms {
+    debounce(active_block, Duration::from_millis(min_stable_ms))
+  } else {
+    active_block
+  };
+
+  let (active_block, transition_class) = if let Some(name) = transition {
+    let (shown_block, class) =
+      follow_timeline(active_block, if_blocks.clone(), name, force_motion);
+
+    (shown_block, Some(class))
+  } else {
+    (active_block, None)
+  };
+
+  // Built before `render`, which takes `if_blocks`
+  if on_branch_enter.is_some() || on_branch_leave.is_some() {
+    notify_branch_changes(
+      active_block,
+      if_blocks.clone(),
+      on_branch_enter,
+      on_branch_leave,
+    );
+  }
+
+  // The content of every mounted branch, to find what to scroll into view
+  let branch_views = store_value(Vec::<(usize, View)>::new());
+
+  // Branches stay mounted with `keep_alive` and `measure_all`, so their state
+  // is kept anyway
+  let branch_states =
+    (persist_state && !keep_alive && !measure_all).then(BranchStates::new);
+
+  let view = if measure_all {
+    render_measure_all(
+      signal,
+      if_blocks,
+      active_block,
+      on_measure,
+      branch_views,
+      metrics,
+    )
+  } else if keep_alive {
+    render_keep_alive(
+      signal,
+      if_blocks,
+      active_block,
+      preload,
+      branch_views,
+      metrics,
+    )
+  } else {
+    (move || {
+      if let Some(i) = active_block.get() {
+        metrics.render();
+
+        let view = render_scoped(
+          &if_blocks,
+          i,
+          IfConditions::new(signal, &if_blocks),
+          branch_states,
+        );
+
+        branch_views.set_value(vec![(i, view.clone())]);
+
+        view
+      } else {
+        branch_views.set_value(Vec::new());
+
+        ().into_view()
+      }
+    })
+    .into_view()
+  };
+
+  if scroll_into_view {
+    scroll_on_enter(active_block, branch_views, scroll_options);
+  }
+
+  let view = if let Some(fallback) = fallback {
+    let fallback = move || active_block.get().is_none().then(|| fallback.run());
+
+    vec![view, fallback.into_view()].into_view()
+  } else {
+    view
+  };
+
+  let view = if let Some(min_height) = reserve_space {
+    let spacer =
+      util::reserve_space(min_height, move || active_block.get().is_none());
+
+    vec![view, spacer].into_view()
+  } else {
+    view
+  };
+
+  let wrapper = (wrapper.is_some() || !attrs.is_empty())
+    .then(|| html::custom(html::Custom::new(wrapper.unwrap_or("div"))));
+
+  let wrapper_has_class = attrs.iter().any(|(name, _)| *name == "class");
+
+  let (view, wrapper) = match (transition_class, wrapper) {
+    (Some(class), Some(wrapper)) if !wrapper_has_class => {
+      (view, Some(wrapper.attr("class", class)))
+    }
+    (Some(class), wrapper) => {
+      (view! { <div class=class>{view}</div> }.into_view(), wrapper)
+    }
+    (None, wrapper) => (view, wrapper),
+  };
+
+  let view = if let Some(wrapper) = wrapper {
+    wrapper.attrs(attrs).child(view).into_view()
+  } else {
+    view
+  };
+
+  if let Some(tag) = shadow_host {
+    render_in_shadow_root(tag, view)
+  } else {
+    view
+  }
+}
+
+/// Returns the only child of an [`If`] if it is a [`Then`].
+fn lone_then(children: &Fragment) -> Option<Transparent> {
+  let mut blocks = util::transparent_children(children);
+
+  let then = blocks.next().filter(|block| {
+    block.downcast_ref::<IfBlock>().is_some_and(IfBlock::is_if)
+  })?;
+
+  blocks.next().is_none().then(|| then.clone())
+}
+
+/// Renders `then`, a lone [`Then`], whenever `signal` is true, without the
+/// branch selection of the general case.
+fn render_lone_then(
+  signal: Memo<bool>,
+  then: Transparent,
+  metrics: MetricsRecorder,
+) -> View {
+  (move || {
+    metrics.evaluation();
+
+    signal
+      .get()
+      .then(|| {
+        metrics.render();
+
+        let block = then.downcast_ref::<IfBlock>()?;
+
+        // Provided so that the branches of a nested `If` do not use the
+        // states of an outer one
+        provide_context(BranchStateScope {
+          branch: block.identity(0),
+          states: None,
+        });
+
+        Some(block.render(IfConditions {
+          signal: true,
+          else_ifs: Vec::new(),
+        }))
+      })
+      .flatten()
+  })
+  .into_view()
+}
+
+/// Calls `on_enter` and `on_leave` as the shown block changes, after the
+/// blocks are swapped, and `on_leave` again when the [`If`] unmounts.
+fn notify_branch_changes(
+  active_block: Memo<Option<usize>>,
+  if_blocks: Rc<[TypedTransparent<IfBlock>]>,
+  on_enter: Option<Callback<BranchId>>,
+  on_leave: Option<Callback<BranchId>>,
+) {
+  let branch_id = move |i: usize| block_at(&if_blocks, i).identity(i);
+
+  let shown = store_value(None::<BranchId>);
+
+  let leave = move || {
+    let branch = shown.try_get_value().flatten();
+
+    if let (Some(on_leave), Some(branch)) = (on_leave, branch) {
+      on_leave.call(branch);
+    }
+  };
+
+  // Effects run after the view is updated, so the new branch is mounted
+  create_effect(move |_| {
+    let active = active_block.get().map(&branch_id);
+
+    if active == shown.get_value() {
+      return;
+    }
+
+    leave();
+
+    shown.set_value(active);
+
+    if let (Some(on_enter), Some(branch)) = (on_enter, active) {
+      on_enter.call(branch);
+    }
+  });
+
+  on_cleanup(leave);
+}
+
+/// Scrolls the first element of the shown branch into view whenever
+/// `active_block` switches to it.
+fn scroll_on_enter(
+  active_block: Memo<Option<usize>>,
+  branch_views: StoredValue<Vec<(usize, View)>>,
+  options: ScrollOptions,
+) {
+  create_effect(move |shown: Option<Option<usize>>| {
+    let active = active_block.get();
+
+    // Nothing was switched from when the effect first runs
+    if let (Some(_), Some(i)) = (shown, active) {
+      // Wait for the branch to be mounted and laid out
+      request_animation_frame(move || {
+        let element = branch_views
+          .try_with_value(|views| {
+            views
+              .iter()
+              .find(|(j, _)| *j == i)
+              .and_then(|(_, view)| util::first_element(view))
+          })
+          .flatten();
+
+        if let Some(element) = element {
+          element
+            .scroll_into_view_with_scroll_into_view_options(&options.into());
+        }
+      });
+    }
+
+    active
+  });
+}
+
+/// Returns the block to show, which follows `active_block`, except while
+/// `pending` is true, where it stays the same.
+fn hold_while_pending(
+  active_block: Memo<Option<usize>>,
+  pending: Signal<bool>,
+) -> Memo<Option<usize>> {
+  create_memo(move |shown: Option<&Option<usize>>| match shown {
+    Some(shown) if pending.get() => *shown,
+    _ => active_block.get(),
+  })
+}
+
+/// Returns the block to show, which follows `active_block` once it has
+/// selected the same block for `min_stable`.
+fn debounce(
+  active_block: Memo<Option<usize>>,
+  min_stable: Duration,
+) -> Memo<Option<usize>> {
+  let stable_block = create_rw_signal(active_block.get_untracked());
+  let timeout = store_value(None::<TimeoutHandle>);
+
+  let cancel = move || {
+    if let Some(timeout) = timeout.try_update_value(Option::take).flatten() {
+      timeout.clear();
+    }
+  };
+
+  create_isomorphic_effect(move |_| {
+    let active = active_block.get();
+
+    cancel();
+
+    if active == stable_block.get_untracked() {
+      return;
+    }
+
+    if !leptos_dom::is_browser() {
+      stable_block.set(active);
+
+      return;
+    }
+
+    let handle = set_timeout_with_handle(
+      move || {
+        stable_block.try_set(active);
+      },
+      min_stable,
+    );
+
+    timeout.set_value(handle.ok());
+  });
+
+  on_cleanup(cancel);
+
+  create_memo(move |_| stable_block.get())
+}
+
+/// Returns the block to show, which lags behind `active_block` until the enter
+/// phase of the [`TransitionTimeline`], and the class of the `<div>` wrapping
+/// the branches.
+fn follow_timeline(
+  active_block: Memo<Option<usize>>,
+  if_blocks: Rc<[TypedTransparent<IfBlock>]>,
+  name: &'static str,
+  force_motion: MaybeSignal<bool>,
+) -> (Memo<Option<usize>>, Signal<String>) {
+  let identity = move |block: Option<usize>| {
+    block.map(|i| block_at(&if_blocks, i).identity(i))
+  };
+
+  let timeline = use_context::<TransitionTimeline>().unwrap_or_default();
+
+  let shown_block = create_rw_signal(active_block.get_untracked());
+  let swapping = create_rw_signal(false);
+
+  create_isomorphic_effect(move |_| {
+    let active = active_block.get();
+
+    let shown = shown_block.get_untracked();
+
+    if active == shown {
+      swapping.set(false);
+    } else if identity(active) == identity(shown) {
+      shown_block.set(active);
+    } else if timeline.start_with(force_motion.get_untracked()) {
+      swapping.set(true);
+    } else {
+      shown_block.set(active);
+    }
+  });
+
+  create_isomorphic_effect(move |_| match timeline.phase().get() {
+    TransitionPhase::Enter if swapping.get_untracked() => {
+      shown_block.set(active_block.get_untracked());
+    }
+    TransitionPhase::Idle => swapping.set(false),
+    _ => {}
+  });
+
+  let class =
+    Signal::derive(move || match (swapping.get(), timeline.phase().get()) {
+      (true, TransitionPhase::Leave) => format!("{name} {name}-leave"),
+      (true, TransitionPhase::Enter) => format!("{name} {name}-enter"),
+      _ => name.to_string(),
+    });
+
+  (create_memo(move |_| shown_block.get()), class)
+}
+
+/// Renders `view` inside the shadow root of a `<{tag}>` custom element, in the
+/// browser only.
+fn render_in_shadow_root(tag: &'static str, view: View) -> View {
+  debug_assert!(
+    tag.contains('-'),
+    "`shadow_host` must be a valid custom element name containing a `-`, \
+     got `{tag}`"
+  );
+
+  let host = html::custom(html::Custom::new(tag));
+
+  if !leptos_dom::is_browser() {
+    return host.child(view).into_view();
+  }
+
+  // Custom elements only dereference to a DOM element once type-erased
+  let shadow_root = host
+    .clone()
+    .into_any()
+    .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+    .expect("failed to attach a shadow root to the `If` shadow host");
+
+  let content = html::div().style("display", "contents").child(view);
+
+  shadow_root
+    .append_child(&content)
+    .expect("failed to append to the shadow root of the `If` shadow host");
+
+  host.into_view()
+}
+
+/// Returns the position of the block which should be rendered, if any, given
+/// whether the block at each position is true.
+///
+/// Blocks are checked lazily, so with [`IfStrategy::FirstMatch`] the
+/// conditions after the first true block are never read.
+fn select_block(
+  if_blocks: &[TypedTransparent<IfBlock>],
+  strategy: IfStrategy,
+  is_true: impl Fn(usize, &IfBlock) -> bool,
+) -> Option<usize> {
+  let mut true_blocks = if_blocks
+    .iter()
+    .enumerate()
+    .filter(|(i, block)| is_true(*i, block));
+
+  match strategy {
+    IfStrategy::FirstMatch => true_blocks.next().map(|(i, _)| i),
+    // `Then` always wins, whatever the priorities of the `ElseIf`s
+    IfStrategy::HighestPriority => true_blocks
+      .min_by_key(|(i, block)| (!block.is_if(), Reverse(block.priority()), *i))
+      .map(|(i, _)| i),
+    IfStrategy::Weighted => {
+      let true_blocks = true_blocks.collect::<Vec<_>>();
+
+      // `Then` always wins, and the `else` block only when no `ElseIf` is true
+      if let Some((i, _)) = true_blocks.iter().find(|(_, block)| block.is_if())
+      {
+        return Some(*i);
+      }
+
+      let candidates = true_blocks
+        .iter()
+        .filter_map(|(i, block)| block.weight().map(|weight| (*i, weight)))
+        .collect::<Vec<_>>();
+
+      pick_weighted(use_experiment_seed(), &candidates)
+        .or_else(|| true_blocks.first().map(|(i, _)| *i))
+    }
+  }
+}
+
+fn block_at(
+  if_blocks: &[TypedTransparent<IfBlock>],
+  i: usize,
+) -> &TypedTransparent<IfBlock> {
+  if_blocks
+    .get(i)
+    .expect("`<If />` has no branch at the given position")
+}
+
+/// Renders the block at `i` under an owner of its own, so that any context
+/// provided by the block, or by its children, is only visible within it, and
+/// is removed along with it.
+fn render_scoped(
+  if_blocks: &[TypedTransparent<IfBlock>],
+  i: usize,
+  conditions: IfConditions,
+  states: Option<BranchStates>,
+) -> View {
+  let block = block_at(if_blocks, i).clone();
+  let branch = block.identity(i);
+
+  let (view, disposer) = as_child_of_current_owner(move |conditions| {
+    // Provided even without `states`, so that the branches of a nested `If`
+    // do not use the states of this one
+    provide_context(BranchStateScope { branch, states });
+
+    block.render(conditions)
+  })(conditions);
+
+  on_cleanup(move || drop(disposer));
+
+  view
+}
+
+/// Returns the view an [`If`] with the given `children` and `strategy` would
+/// render for a snapshot of its conditions, without mounting an [`If`].
+///
+/// The [`ElseIf`] signals are ignored in favor of `conditions.else_ifs`, in
+/// which a missing value counts as false. Nothing is reactive: the view is
+/// chosen once, and is not updated when any signal changes. This is meant for
+/// tests and tooling inspecting the branches of an [`If`].
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   render_branch(
+///     view! {
+///       <Then>"A is true!"</Then>
+///       <ElseIf signal=false>"B is true!"</ElseIf>
+///       <Else>"Both A and B are false!"</Else>
+///     },
+///     IfConditions {
+///       signal: false,
+///       else_ifs: vec![true],
+///     },
+///     IfStrategy::FirstMatch,
+///   )
+/// });
+///
+/// assert!(html.contains("B is true!"));
+/// assert!(!html.contains("A is true!"));
+/// ```
+pub fn render_branch(
+  children: Fragment,
+  conditions: IfConditions,
+  strategy: IfStrategy,
+) -> View {
+  let if_blocks = collect_transparent::<IfBlock>(&children);
+
+  #[cfg(debug_assertions)]
+  run_debug_checks(&if_blocks, false, None);
+
+  let mut else_ifs = conditions.else_ifs.iter().copied();
+
+  let is_true = if_blocks
+    .iter()
+    .map(|block| match &**block {
+      IfBlock::If { .. } => conditions.signal,
+      IfBlock::ElseIf { .. } => else_ifs.next().unwrap_or_default(),
+      IfBlock::Else { .. } | IfBlock::ElseWith { .. } => true,
+      IfBlock::OnError { .. } => false,
+    })
+    .collect::<Vec<_>>();
+
+  if let Some(i) = select_block(&if_blocks, strategy, |i, _| is_true[i]) {
+    render_scoped(&if_blocks, i, conditions, None)
+  } else {
+    ().into_view()
+  }
+}
+
+fn render_keep_alive(
+  signal: Memo<bool>,
+  if_blocks: Rc<[TypedTransparent<IfBlock>]>,
+  active_block: Memo<Option<usize>>,
+  mut preload: Vec<usize>,
+  branch_views: StoredValue<Vec<(usize, View)>>,
+  metrics: MetricsRecorder,
+) -> View {
+  // Blocks are built by the effect below, but must belong to the `If` itself,
+  // or they would be disposed of whenever the effect runs again
+  let owner = Owner::current();
+
+  let build_block = move |i: usize| {
+    metrics.render();
+
+    let render = || {
+      render_scoped(&if_blocks, i, IfConditions::new(signal, &if_blocks), None)
+    };
+
+    let children = match owner {
+      Some(owner) => with_owner(owner, render),
+      None => render(),
+    };
+
+    branch_views.update_value(|views| views.push((i, children.clone())));
+
+    let display = move || {
+      if active_block.get() == Some(i) {
+        "contents"
+      } else {
+        "none"
+      }
+    };
+
+    view! { <div style:display=display>{children}</div> }.into_view()
+  };
+
+  preload.sort_unstable();
+  preload.dedup();
+
+  let built_blocks = create_rw_signal(
+    preload
+      .into_iter()
+      .map(|i| (i, build_block(i)))
+      .collect::<Vec<_>>(),
+  );
+
+  // Build blocks the first time they are shown
+  create_isomorphic_effect(move |_| {
+    if let Some(i) = active_block.get() {
+      if !built_blocks.with_untracked(|blocks| blocks.iter().any(|b| b.0 == i))
+      {
+        let block = build_block(i);
+
+        built_blocks.update(|blocks| blocks.push((i, block)));
+      }
+    }
+  });
+
+  (move || {
+    built_blocks.with(|blocks| {
+      blocks.iter().map(|(_, block)| block.clone()).collect_view()
+    })
+  })
+  .into_view()
+}
+
+fn render_measure_all(
+  signal: Memo<bool>,
+  if_blocks: Rc<[TypedTransparent<IfBlock>]>,
+  active_block: Memo<Option<usize>>,
+  on_measure: Option<Callback<(BranchId, Rect)>>,
+  branch_views: StoredValue<Vec<(usize, View)>>,
+  metrics: MetricsRecorder,
+) -> View {
+  (0..if_blocks.len())
+    .map(|i| {
+      metrics.render();
+
+      let children = render_scoped(
+        &if_blocks,
+        i,
+        IfConditions::new(signal, &if_blocks),
+        None,
+      );
+
+      branch_views.update_value(|views| views.push((i, children.clone())));
+
+      let wrapper = create_node_ref::<html::Div>();
+
+      if let Some(on_measure) = on_measure {
+        let branch = block_at(&if_blocks, i).identity(i);
+
+        observe_size(
+          wrapper,
+          Callback::new(move |rect| on_measure.call((branch, rect))),
+        );
+      }
+
+      let style = move || {
+        if active_block.get() == Some(i) {
+          ""
+        } else {
+          "position: absolute; visibility: hidden"
+        }
+      };
+
+      view! { <div node_ref=wrapper style=style>{children}</div> }.into_view()
+    })
+    .collect_view()
+}
+
+/// Returns state local to the branch of an [`If`] it is called in, which is
+/// created with `init` the first time the branch is shown.
 ///
-/// [`Then`] is the only required child component, as it's what will be shown
-/// when the [`If`]'s signal is true.
+/// With `persist_state` on the [`If`], the state outlives the branch: when
+/// the branch is shown again after another one was, it gets back the same
+/// [`StoredValue`], with whatever was stored in it, rather than calling
+/// `init` again. Without it, or outside of an [`If`], this is the same as
+/// [`store_value`], and the state is lost when the branch unmounts.
 ///
-/// For more docs on allowed child components, check out [`IfProps::children`].
+/// Each branch keeps one state per type, so use a struct to keep several
+/// values.
 ///
 /// # Examples
-///
-/// ### Simple `if`
 /// ```rust
 /// use leptos::*;
 /// use leptos_declarative::prelude::*;
 ///
-/// # let _ = create_scope(create_runtime(), |cx| {
-/// let (a, _) = create_signal(cx, true);
+/// #[derive(Default)]
+/// struct Draft {
+///   text: String,
+/// }
 ///
-/// view! { cx,
-/// <If signal=a>
-///   <Then>"a is true!"</Then>
+/// #[component]
+/// fn Editor() -> impl IntoView {
+///   // Still there when switching back from the preview
+///   let draft = use_branch_state(Draft::default);
+///
+///   view! {
+///     <textarea
+///       prop:value=draft.with_value(|draft| draft.text.clone())
+///       on:input=move |ev| {
+///         draft.update_value(|draft| draft.text = event_target_value(&ev))
+///       }
+///     />
+///   }
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (editing, _) = create_signal(true);
+///
+/// view! {
+/// <If signal=editing persist_state=true>
+///   <Then><Editor /></Then>
+///   <Else>"Preview"</Else>
 /// </If>
 /// };
-/// # });
+/// # runtime.dispose();
 /// ```
+pub fn use_branch_state<T: 'static>(
+  init: impl FnOnce() -> T,
+) -> StoredValue<T> {
+  match use_context::<BranchStateScope>() {
+    Some(BranchStateScope {
+      branch,
+      states: Some(states),
+    }) => states.get_or_insert(branch, init),
+    _ => store_value(init()),
+  }
+}
+
+/// The branch being rendered, and where its state is kept, if anywhere.
+#[derive(Clone, Copy)]
+struct BranchStateScope {
+  branch: BranchId,
+  states: Option<BranchStates>,
+}
+
+/// A state created by a branch with [`use_branch_state`], as a
+/// [`StoredValue`] of its type, along with that type.
+type BranchState = (BranchId, TypeId, Box<dyn Any>);
+
+/// The state of the branches of an [`If`] with `persist_state`.
+#[derive(Clone, Copy)]
+struct BranchStates {
+  /// The states of each branch.
+  states: StoredValue<Vec<BranchState>>,
+  /// The owner of the [`If`], which owns the states rather than the branch
+  /// which created them, so that they outlive it.
+  owner: Option<Owner>,
+}
+
+impl BranchStates {
+  fn new() -> Self {
+    Self {
+      states: store_value(Vec::new()),
+      owner: Owner::current(),
+    }
+  }
+
+  fn get_or_insert<T: 'static>(
+    self,
+    branch: BranchId,
+    init: impl FnOnce() -> T,
+  ) -> StoredValue<T> {
+    let existing = self.states.with_value(|states| {
+      states.iter().find_map(|(other, type_id, state)| {
+        if *other == branch && *type_id == TypeId::of::<T>() {
+          state.downcast_ref::<StoredValue<T>>().copied()
+        } else {
+          None
+        }
+      })
+    });
+
+    existing.unwrap_or_else(|| {
+      let create = move || store_value(init());
+
+      let state = match self.owner {
+        Some(owner) => with_owner(owner, create),
+        None => create(),
+      };
+
+      self.states.update_value(|states| {
+        states.push((branch, TypeId::of::<T>(), Box::new(state)));
+      });
+
+      state
+    })
+  }
+}
+
+/// This must be the first direct child of [`If`]. It will be shown
+/// iff the signal provided to [`If`] is true.
+#[component(transparent)]
+pub fn Then(
+  /// What you want to show when this `if` expression is evaluated.
+  children: Box<dyn Fn() -> Fragment>,
+  /// Attributes for a `<div>` wrapping the content, set with the `attr:`
+  /// prefix, e.g. `attr:class="branch"`. Without any, the content is not
+  /// wrapped.
+  #[prop(attrs)]
+  attrs: Vec<(&'static str, Attribute)>,
+  /// Identifies this branch for `transition`: switching between branches with
+  /// the same key does not transition. Defaults to the position of the
+  /// branch.
+  #[prop(optional)]
+  key: Option<&'static str>,
+) -> impl IntoView {
+  IfBlock::If {
+    key,
+    children: wrap_children(children, attrs),
+  }
+}
+
+/// This must be the direct child of an [`If`] component, and be placed after
+/// the [`Then`] component. It will render it's children iff the [`If`] signal
+/// is false and all other [`ElseIf`] signals are false and this one is true.
 ///
-/// ### `if/else`
+/// # Examples
+///
+/// ### `else if not`
 /// ```rust
 /// use leptos::*;
 /// use leptos_declarative::prelude::*;
 ///
-/// # let _ = create_scope(create_runtime(), |cx| {
-/// let (a, _) = create_signal(cx, true);
+/// # let runtime = create_runtime();
+/// let (logged_out, _) = create_signal(false);
+/// let (verified, _) = create_signal(false);
 ///
-/// view! { cx,
-/// <If signal=a>
-///   <Then>"A is true!"</Then>
-///   <Else>"A is false!"</Else>
+/// view! {
+/// <If signal=logged_out>
+///   <Then>"Please log in."</Then>
+///   <ElseIf signal=verified invert=true>"Please verify your email."</ElseIf>
+///   <Else>"Welcome!"</Else>
 /// </If>
 /// };
-/// # });
+/// # runtime.dispose();
 /// ```
 ///
-/// ### `if/else-if`
+/// ### Expensive conditions
+///
+/// The condition is memoized, so it is only evaluated again when a signal it
+/// reads changes, no matter how many times [`If`] reads it.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::{
+///   cell::Cell,
+///   rc::Rc,
+/// };
+///
+/// let evaluations = Rc::new(Cell::new(0));
+///
+/// let html = leptos::ssr::render_to_string({
+///   let evaluations = evaluations.clone();
+///
+///   move || {
+///     let expensive = move || {
+///       evaluations.set(evaluations.get() + 1);
+///
+///       false
+///     };
+///
+///     view! {
+///     <If signal=false>
+///       <Then>"A"</Then>
+///       <ElseIf signal=expensive.clone()>"B"</ElseIf>
+///       <ElseWith view=|conditions: IfConditions| {
+///         format!("else ifs: {:?}", conditions.else_ifs)
+///       } />
+///     </If>
+///     }
+///   }
+/// });
+///
+/// assert!(html.contains("else ifs: [false]"));
+///
+/// // Both choosing the branch and building the `IfConditions` of `ElseWith`
+/// // read the condition, yet it was only evaluated once
+/// assert_eq!(evaluations.get(), 1);
+/// ```
+#[component(transparent)]
+pub fn ElseIf<C>(
+  /// The condition.
+  signal: C,
+  /// Negates `signal`, so that this branch is rendered when it is false.
+  #[prop(optional)]
+  invert: bool,
+  /// With [`IfStrategy::HighestPriority`], the true [`ElseIf`] with the
+  /// highest priority is rendered. Defaults to `0`.
+  #[prop(optional)]
+  priority: i32,
+  /// With [`IfStrategy::Weighted`], how likely this branch is to be picked
+  /// among the true [`ElseIf`]s, relative to their weights. A weight of `0`
+  /// is never picked while another true branch has a weight. Defaults to
+  /// `1`.
+  #[prop(optional)]
+  weight: Option<u32>,
+  /// What you want to show when this `else if` expression is evaluated.
+  children: Box<dyn Fn() -> Fragment>,
+  /// Attributes for a `<div>` wrapping the content, set with the `attr:`
+  /// prefix, e.g. `attr:class="branch"`. Without any, the content is not
+  /// wrapped.
+  #[prop(attrs)]
+  attrs: Vec<(&'static str, Attribute)>,
+  /// Identifies this branch for `transition`: switching between branches with
+  /// the same key does not transition. Defaults to the position of the
+  /// branch.
+  #[prop(optional)]
+  key: Option<&'static str>,
+) -> impl IntoView
+where
+  C: IntoCondition + 'static,
+{
+  let signal = create_memo(move |_| signal.evaluate() != invert);
+
+  IfBlock::ElseIf {
+    key,
+    signal,
+    priority,
+    weight: weight.unwrap_or(1),
+    children: wrap_children(children, attrs),
+  }
+}
+
+/// This must be the direct child of an [`If`] component, and be the last component.
+/// It will render it's children iff all other signals are false.
+#[component(transparent)]
+pub fn Else(
+  /// What you want to show when all other signals are false.
+  children: Box<dyn Fn() -> Fragment>,
+  /// Attributes for a `<div>` wrapping the content, set with the `attr:`
+  /// prefix, e.g. `attr:class="branch"`. Without any, the content is not
+  /// wrapped.
+  #[prop(attrs)]
+  attrs: Vec<(&'static str, Attribute)>,
+  /// Identifies this branch for `transition`: switching between branches with
+  /// the same key does not transition. Defaults to the position of the
+  /// branch.
+  #[prop(optional)]
+  key: Option<&'static str>,
+) -> impl IntoView {
+  IfBlock::Else {
+    key,
+    children: wrap_children(children, attrs),
+  }
+}
+
+/// Wraps the content of a branch in a `<div>` with `attrs`, if there are any.
+fn wrap_children(
+  children: Box<dyn Fn() -> Fragment>,
+  attrs: Vec<(&'static str, Attribute)>,
+) -> Box<dyn Fn() -> Fragment> {
+  if attrs.is_empty() {
+    return children;
+  }
+
+  Box::new(move || {
+    Fragment::new(vec![html::div()
+      .attrs(attrs.clone())
+      .child(children())
+      .into_view()])
+  })
+}
+
+/// Like [`Else`], but its view receives the state of every condition of the
+/// [`If`] at the time it is rendered. This is useful for showing informative
+/// "nothing matched because..." states during development.
+///
+/// This must be the direct child of an [`If`] component, and be the last
+/// component, in place of [`Else`].
+///
+/// # Examples
 /// ```rust
 /// use leptos::*;
 /// use leptos_declarative::prelude::*;
 ///
-/// # let _ = create_scope(create_runtime(), |cx| {
-/// let (a, _) = create_signal(cx, true);
-/// let (b, _) = create_signal(cx, false);
+/// # let runtime = create_runtime();
+/// let (a, _) = create_signal(false);
+/// let (b, _) = create_signal(false);
 ///
-/// view! { cx,
+/// view! {
 /// <If signal=a>
 ///   <Then>"A is true!"</Then>
 ///   <ElseIf signal=b>"B is true!"</ElseIf>
-///   <Else>"Both A and B are false!"</Else>
+///   <ElseWith view=|conditions: IfConditions| view! {
+///     <pre>{format!("{conditions:?}")}</pre>
+///   } />
 /// </If>
 /// };
-/// # });
+/// # runtime.dispose();
 /// ```
+#[component(transparent)]
+pub fn ElseWith<F, IV>(
+  /// What you want to show when all other signals are false, given their
+  /// state.
+  view: F,
+  /// Identifies this branch for `transition`: switching between branches with
+  /// the same key does not transition. Defaults to the position of the
+  /// branch.
+  #[prop(optional)]
+  key: Option<&'static str>,
+) -> impl IntoView
+where
+  F: Fn(IfConditions) -> IV + 'static,
+  IV: IntoView,
+{
+  IfBlock::ElseWith {
+    key,
+    children: Box::new(move |conditions| view(conditions).into_view()),
+  }
+}
+
+/// This must be the direct child of an [`If`] component, before any [`Else`]
+/// or [`ElseWith`]. It will be shown iff the `error` signal of the [`If`] is
+/// true, in place of any other branch.
 ///
-/// ### `MaybeSignal`
-///
-/// The `signal` prop of the [`If`] and [`ElseIf`] component allow
-/// taking any value that implements `Into<MaybeSignal<bool>>`.
-/// This means that you can pass in [`bool`] values that are not
-/// strictly signals, such as in the following.
-///
+/// # Examples
 /// ```rust
 /// use leptos::*;
 /// use leptos_declarative::prelude::*;
 ///
-/// # let _ = create_scope(create_runtime(), |cx| {
+/// fn render(is_admin: Option<bool>, failed: bool) -> String {
+///   leptos::ssr::render_to_string(move || {
+///     view! {
+///     <If signal=is_admin.unwrap_or_default() error=move || failed>
+///       <Then>"Admin tools"</Then>
+///       <OnError>"Could not load your account."</OnError>
+///       <Else>"Nothing to manage."</Else>
+///     </If>
+///     }
+///   })
+///   .to_string()
+/// }
 ///
-/// view! { cx,
-/// <If signal=true>
-///   <Then>"a is true!"</Then>
-///   <ElseIf signal=false>"b is true!"</ElseIf>
-/// </If>
-/// };
-/// # });
+/// assert!(render(Some(true), false).contains("Admin tools"));
+/// assert!(render(Some(false), false).contains("Nothing to manage."));
+///
+/// // Neither the true nor the false branch is shown on errors
+/// let html = render(None, true);
+///
+/// assert!(html.contains("Could not load your account."));
+/// assert!(!html.contains("Admin tools"));
+/// assert!(!html.contains("Nothing to manage."));
 /// ```
-#[component]
-pub fn If(
-  cx: Scope,
-  /// The bool signal.
-  #[prop(into)]
-  signal: MaybeSignal<bool>,
-  /// The `if` conditions you would like to evaluate.
-  ///
-  /// Children must be any
-  /// - [`Then`]
-  /// - [`ElseIf`]
-  /// - [`Else`]
-  ///
-  /// Any other child not in the above list will not be rendered.
-  ///
-  /// [`Then`] must be present and the first child.
-  ///
-  /// [`Else`] must be the last child.
-  children: Box<dyn Fn(Scope) -> Fragment>,
+#[component(transparent)]
+pub fn OnError(
+  /// What you want to show when the `error` signal of the [`If`] is true.
+  children: Box<dyn Fn() -> Fragment>,
+  /// Attributes for a `<div>` wrapping the content, set with the `attr:`
+  /// prefix, e.g. `attr:class="branch"`. Without any, the content is not
+  /// wrapped.
+  #[prop(attrs)]
+  attrs: Vec<(&'static str, Attribute)>,
+  /// Identifies this branch for `transition`: switching between branches with
+  /// the same key does not transition. Defaults to the position of the
+  /// branch.
+  #[prop(optional)]
+  key: Option<&'static str>,
 ) -> impl IntoView {
-  // Memoize the signal
-  let signal = create_memo(cx, move |_| signal.get());
-
-  let children = children(cx);
+  IfBlock::OnError {
+    key,
+    children: wrap_children(children, attrs),
+  }
+}
 
-  // Get the condition blocks
-  let if_blocks = children
-    .as_children()
-    .iter()
-    .filter_map(View::as_transparent)
-    .cloned()
-    .collect::<Vec<_>>();
+/// Compiles a branch of an [`If`] only when a `cfg` predicate holds, such as
+/// a cargo feature being enabled.
+///
+/// Unlike a runtime condition, a branch whose predicate is false is removed at
+/// compile time, along with everything only it uses, so feature-flagged UI
+/// does not end up in the bundle. The predicate takes the same syntax as
+/// `#[cfg(...)]`, and is evaluated against the crate using the macro.
+///
+/// An optional `else` branch is compiled in its place when the predicate is
+/// false. As an [`If`] always needs a [`Then`], gating a [`Then`] requires an
+/// `else` with another [`Then`]. Gating an [`ElseIf`] or an [`Else`] does not.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   let (a, _) = create_signal(false);
+///   let (b, _) = create_signal(true);
+///
+///   view! {
+///   <If signal=a>
+///     {cfg_branch!(
+///       feature = "beta" => view! { <Then>"New dashboard"</Then> },
+///       else => view! { <Then>"Dashboard"</Then> },
+///     )}
+///     {cfg_branch!(
+///       feature = "beta" => view! { <ElseIf signal=b>"Beta banner"</ElseIf> }
+///     )}
+///     <Else>"Nothing to show."</Else>
+///   </If>
+///   }
+/// });
+///
+/// // The doctest is not built with a `beta` feature
+/// assert!(!html.contains("Beta banner"));
+/// assert!(html.contains("Nothing to show."));
+/// ```
+#[macro_export]
+macro_rules! cfg_branch {
+  ($cfg:meta => $view:expr $(, else => $else:expr)? $(,)?) => {{
+    #[cfg($cfg)]
+    let view = ::leptos::IntoView::into_view($view);
+    #[cfg(not($cfg))]
+    let view = ::leptos::IntoView::into_view(($($else)?));
 
-  #[cfg(debug_assertions)]
-  run_debug_checks(&if_blocks);
+    view
+  }};
+}
 
-  let last_rendered_block = Cell::<Option<usize>>::new(None);
-  let child = Cell::new(().into_view(cx));
+/// The state of the conditions of an [`If`], as received by [`ElseWith`].
+///
+/// It is also provided as context to the children of whichever branch is
+/// rendered, so that they can tell why they were rendered without having the
+/// signals passed down to them. The state is taken when the branch is
+/// rendered, and is not updated afterwards.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[component]
+/// fn Banner() -> impl IntoView {
+///   let conditions = use_context::<IfConditions>().unwrap_or_default();
+///
+///   if conditions.else_ifs.first() == Some(&true) {
+///     "Rendered because the first `ElseIf` is true."
+///   } else {
+///     "Rendered for some other reason."
+///   }
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <If signal=false>
+///     <Then>"A is true!"</Then>
+///     <ElseIf signal=true><Banner /></ElseIf>
+///   </If>
+///   }
+/// });
+///
+/// assert!(html.contains("Rendered because the first `ElseIf` is true."));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IfConditions {
+  /// The value of the [`If`] signal.
+  pub signal: bool,
+  /// The value of each [`ElseIf`] signal, in order, after applying `invert`.
+  pub else_ifs: Vec<bool>,
+}
 
-  move || {
-    let mut if_blocks = if_blocks
+impl IfConditions {
+  fn new(signal: Memo<bool>, if_blocks: &[TypedTransparent<IfBlock>]) -> Self {
+    let else_ifs = if_blocks
       .iter()
-      .filter_map(Transparent::downcast_ref::<IfBlock>)
-      .enumerate();
-
-    // Subscribe all <ElseIf /> blocks
-    if_blocks.clone().skip(1).for_each(|(_, block)| {
-      if let IfBlock::ElseIf { signal, .. } = block {
-        signal.track();
-      }
-    });
-
-    if signal.get() {
-      if last_rendered_block.get() != Some(0) {
-        last_rendered_block.set(Some(0));
+      .filter_map(|block| {
+        if let IfBlock::ElseIf { signal, .. } = &**block {
+          Some(signal.get_untracked())
+        } else {
+          None
+        }
+      })
+      .collect();
 
-        let new_child = if_blocks.next().unwrap().1.render(cx).into_view(cx);
-
-        child.set(new_child);
-      }
-    } else if let Some((i, block)) =
-      if_blocks.find(|(_, block)| block.is_true())
-    {
-      if last_rendered_block.get() != Some(i) {
-        last_rendered_block.set(Some(i));
+    Self {
+      signal: signal.get_untracked(),
+      else_ifs,
+    }
+  }
+}
 
-        let new_child = block.render(cx).into_view(cx);
+/// How [`If`] chooses which [`ElseIf`] to render when several of them are
+/// true.
+///
+/// [`Then`] is always rendered when the [`If`] signal is true, and the `else`
+/// branch only when no [`ElseIf`] is, whichever the strategy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IfStrategy {
+  /// The first true [`ElseIf`], in declaration order, is rendered.
+  #[default]
+  FirstMatch,
+  /// The true [`ElseIf`] with the highest `priority` is rendered, regardless
+  /// of its position. On equal priority, the first one in declaration order
+  /// wins.
+  ///
+  /// Every [`ElseIf`] condition is evaluated, rather than stopping at the
+  /// first true one.
+  HighestPriority,
+  /// One of the true [`ElseIf`]s is picked at random, in proportion to
+  /// their `weight`, with the experiment seed in context, e.g. to render the
+  /// variants of an A/B experiment. Please refer to the
+  /// [`experiment`](crate::experiment) module for how the seed makes the
+  /// pick reproducible.
+  ///
+  /// Every [`ElseIf`] condition is evaluated, rather than stopping at the
+  /// first true one.
+  Weighted,
+}
 
-        child.set(new_child);
-      }
-    } else {
-      last_rendered_block.set(None);
+/// How [`If`] scrolls a branch into view with `scroll_into_view`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScrollOptions {
+  /// Whether to scroll smoothly or jump.
+  pub behavior: ScrollBehavior,
+  /// Where to align the branch vertically within the scrolled area.
+  pub block: ScrollAlignment,
+}
 
-      child.set(().into_view(cx));
-    }
+impl From<ScrollOptions> for web_sys::ScrollIntoViewOptions {
+  fn from(options: ScrollOptions) -> Self {
+    let into_view_options = Self::new();
 
-    let view = child.take();
-    child.set(view.clone());
+    into_view_options.set_behavior(match options.behavior {
+      ScrollBehavior::Smooth => web_sys::ScrollBehavior::Smooth,
+      ScrollBehavior::Auto => web_sys::ScrollBehavior::Auto,
+    });
+    into_view_options.set_block(match options.block {
+      ScrollAlignment::Start => web_sys::ScrollLogicalPosition::Start,
+      ScrollAlignment::Center => web_sys::ScrollLogicalPosition::Center,
+      ScrollAlignment::End => web_sys::ScrollLogicalPosition::End,
+      ScrollAlignment::Nearest => web_sys::ScrollLogicalPosition::Nearest,
+    });
 
-    view
+    into_view_options
   }
 }
 
-/// This must be the first direct child of [`If`]. It will be shown
-/// iff the signal provided to [`If`] is true.
-#[component(transparent)]
-pub fn Then(
-  cx: Scope,
-  /// What you want to show when this `if` expression is evaluated.
-  children: Box<dyn Fn(Scope) -> Fragment>,
-) -> impl IntoView {
-  let _ = cx;
-
-  IfBlock::If { children }
+/// Whether [`ScrollOptions`] scroll smoothly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScrollBehavior {
+  /// Scrolls smoothly.
+  #[default]
+  Smooth,
+  /// Scrolls as the `scroll-behavior` CSS property of the scrolled area
+  /// says, which jumps by default.
+  Auto,
 }
 
-/// This must be the direct child of an [`If`] component, and be placed after
-/// the [`Then`] component. It will render it's children iff the [`If`] signal
-/// is false and all other [`ElseIf`] signals are false and this one is true.
-#[component(transparent)]
-pub fn ElseIf(
-  cx: Scope,
-  /// The bool signal.
-  #[prop(into)]
-  signal: MaybeSignal<bool>,
-  /// What you want to show when this `else if` expression is evaluated.
-  children: Box<dyn Fn(Scope) -> Fragment>,
-) -> impl IntoView {
-  let signal = create_memo(cx, move |_| signal.get());
-
-  IfBlock::ElseIf { signal, children }
+/// Where [`ScrollOptions`] align the branch, mirroring the `block` option of
+/// `Element.scrollIntoView()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScrollAlignment {
+  /// Aligns the top of the branch with the top of the scrolled area.
+  Start,
+  /// Centers the branch in the scrolled area.
+  Center,
+  /// Aligns the bottom of the branch with the bottom of the scrolled area.
+  End,
+  /// Scrolls as little as possible for the branch to be in view, and not at
+  /// all if it already is.
+  #[default]
+  Nearest,
 }
 
-/// This must be the direct child of an [`If`] component, and be the last component.
-/// It will render it's children iff all other signals are false.
-#[component(transparent)]
-pub fn Else(
-  cx: Scope,
-  /// What you want to show when all other signals are false.
-  children: Box<dyn Fn(Scope) -> Fragment>,
-) -> impl IntoView {
-  let _ = cx;
-
-  IfBlock::Else { children }
+/// Identifies a branch of an [`If`], e.g. for `transition`, which only runs
+/// when a different branch is shown, or for `on_branch_enter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BranchId {
+  /// The `key` of the branch.
+  Key(&'static str),
+  /// The position of a branch without a `key` among the branches, starting
+  /// from 0 for [`Then`].
+  Position(usize),
 }
 
-/// Represents an if block which is returned by [`Then`], [`ElseIf`]
-/// or [`Else`] components.
+/// Represents an if block which is returned by [`Then`], [`ElseIf`],
+/// [`Else`] or [`ElseWith`] components.
 pub enum IfBlock {
   /// The initial `if` condition, returned by [`Then`].
   If {
+    /// Identifies the branch for transitions, please refer to [`If`].
+    key: Option<&'static str>,
     /// The children method.
-    children: Box<dyn Fn(Scope) -> Fragment>,
+    children: Box<dyn Fn() -> Fragment>,
   },
   /// An `else if` condition, returned by [`ElseIf`].
   ElseIf {
+    /// Identifies the branch for transitions, please refer to [`If`].
+    key: Option<&'static str>,
     /// The signal which must evaluate to true to be rendered.
     signal: Memo<bool>,
+    /// The priority used by [`IfStrategy::HighestPriority`].
+    priority: i32,
+    /// The weight used by [`IfStrategy::Weighted`].
+    weight: u32,
     /// The children method.
-    children: Box<dyn Fn(Scope) -> Fragment>,
+    children: Box<dyn Fn() -> Fragment>,
   },
   /// The `else` condition, returned by [`Else`].
   Else {
+    /// Identifies the branch for transitions, please refer to [`If`].
+    key: Option<&'static str>,
+    /// The children method.
+    children: Box<dyn Fn() -> Fragment>,
+  },
+  /// The `else` condition, returned by [`ElseWith`].
+  ElseWith {
+    /// Identifies the branch for transitions, please refer to [`If`].
+    key: Option<&'static str>,
+    /// The children method.
+    children: Box<dyn Fn(IfConditions) -> View>,
+  },
+  /// The error branch, returned by [`OnError`].
+  OnError {
+    /// Identifies the branch for transitions, please refer to [`If`].
+    key: Option<&'static str>,
     /// The children method.
-    children: Box<dyn Fn(Scope) -> Fragment>,
+    children: Box<dyn Fn() -> Fragment>,
   },
 }
 
@@ -261,46 +2513,131 @@ impl IfBlock {
     }
   }
 
+  /// The priority of this block under [`IfStrategy::HighestPriority`], with
+  /// the `else` block always coming last.
+  fn priority(&self) -> i32 {
+    if let Self::ElseIf { priority, .. } = self {
+      *priority
+    } else {
+      i32::MIN
+    }
+  }
+
+  /// The weight of this block under [`IfStrategy::Weighted`], if it is an
+  /// [`ElseIf`].
+  fn weight(&self) -> Option<u32> {
+    if let Self::ElseIf { weight, .. } = self {
+      Some(*weight)
+    } else {
+      None
+    }
+  }
+
+  /// The identity of the block at position `i` for transitions.
+  fn identity(&self, i: usize) -> BranchId {
+    let key = match self {
+      Self::If { key, .. }
+      | Self::ElseIf { key, .. }
+      | Self::Else { key, .. }
+      | Self::ElseWith { key, .. }
+      | Self::OnError { key, .. } => *key,
+    };
+
+    key.map_or(BranchId::Position(i), BranchId::Key)
+  }
+
   fn is_if(&self) -> bool {
     matches!(self, Self::If { .. })
   }
 
   fn is_else(&self) -> bool {
-    matches!(self, Self::Else { .. })
+    matches!(self, Self::Else { .. } | Self::ElseWith { .. })
+  }
+
+  fn is_on_error(&self) -> bool {
+    matches!(self, Self::OnError { .. })
   }
 
-  fn render(&self, cx: Scope) -> Fragment {
+  /// Renders the children, providing them with `conditions` as context.
+  fn render(&self, conditions: IfConditions) -> View {
+    provide_context(conditions.clone());
+
     match self {
-      Self::If { children } => children(cx),
-      Self::ElseIf { children, .. } => children(cx),
-      Self::Else { children } => children(cx),
+      Self::If { children, .. } => children().into_view(),
+      Self::ElseIf { children, .. } => children().into_view(),
+      Self::Else { children, .. } => children().into_view(),
+      Self::ElseWith { children, .. } => children(conditions),
+      Self::OnError { children, .. } => children().into_view(),
     }
   }
 }
 
 impl IntoView for IfBlock {
-  fn into_view(self, _: Scope) -> View {
+  fn into_view(self) -> View {
     View::Transparent(Transparent::new(self))
   }
 }
 
+/// Warns when the [`If`] unmounts if its condition never changed while it was
+/// mounted, which usually means the condition is not reactive, e.g. a
+/// closure which forgot to read a signal, and a static branch would do.
+///
+/// Only checked in the browser, where an [`If`] lives longer than a single
+/// render.
+#[cfg(all(debug_assertions, feature = "strict-checks"))]
+fn warn_on_constant_condition(
+  signal: Memo<bool>,
+  debug_name: Option<&'static str>,
+) {
+  if !leptos_dom::is_browser() {
+    return;
+  }
+
+  let changes = Rc::new(Cell::new(0_usize));
+
+  create_effect({
+    let changes = changes.clone();
+
+    move |_| {
+      signal.track();
+
+      changes.set(changes.get() + 1);
+    }
+  });
+
+  on_cleanup(move || {
+    if changes.get() <= 1 {
+      logging::warn!(
+        "the condition of {} never changed while it was mounted; if this is \
+         intended, render the branch directly instead, otherwise make sure \
+         the condition reads a signal",
+        component_name("If", debug_name),
+      );
+    }
+  });
+}
+
 #[cfg(debug_assertions)]
-fn run_debug_checks(if_blocks: &[Transparent]) {
-  let if_blocks = if_blocks
-    .iter()
-    .filter_map(Transparent::downcast_ref::<IfBlock>);
+fn run_debug_checks(
+  if_blocks: &[TypedTransparent<IfBlock>],
+  has_fallback: bool,
+  debug_name: Option<&str>,
+) {
+  let if_ = component_name("If", debug_name);
+
+  let if_blocks = if_blocks.iter().map(|block| &**block);
 
   // Make sure <Show /> is first
   assert!(
     if_blocks.clone().next().unwrap().is_if(),
-    "`<Show />` must be the first child of `<If />`"
+    "`<Show />` must be the first child of {if_}"
   );
 
   // Make sure there is no more than 1 <Show />
   assert_eq!(
     if_blocks.clone().filter(|block| block.is_if()).count(),
     1,
-    "there must not be more than 1 `<Show />` children within `<If />`"
+    "there must not be more than 1 `<Show />` children within {if_}"
   );
 
   // Make sure <Else /> is last
@@ -308,13 +2645,30 @@ fn run_debug_checks(if_blocks: &[Transparent]) {
     assert_eq!(
       pos,
       if_blocks.clone().count() - 1,
-      "`<Else />` must be the last child of `<If />`"
+      "`<Else />` must be the last child of {if_}"
     );
   }
 
   // Make sure there is no more than 1 <Else />
   assert!(
-    if_blocks.filter(|block| block.is_else()).count() <= 1,
-    "there must not be more than 1 `<Else />` children within `<If />`"
+    if_blocks.clone().filter(|block| block.is_else()).count() <= 1,
+    "there must not be more than 1 `<Else />` children within {if_}"
+  );
+
+  // Make sure there is no more than 1 <OnError />
+  assert!(
+    if_blocks
+      .clone()
+      .filter(|block| block.is_on_error())
+      .count()
+      <= 1,
+    "there must not be more than 1 `<OnError />` children within {if_}"
+  );
+
+  // Make sure there is either an <Else /> or a fallback, not both
+  assert!(
+    !(has_fallback && if_blocks.clone().any(IfBlock::is_else)),
+    "{if_} has both an `<Else />` child and a `fallback`, only the `<Else />` \
+     would ever be shown, use one or the other"
   );
 }
@@ -4,9 +4,10 @@
 //! constructs in the [`leptos`] web framework not directly
 //! provided by default.
 //!
-//! This crate provides 2 main components
+//! This crate provides 3 main components
 //!
 //! - [`If`](if_::If)
+//! - [`Match`](match_::Match)
 //! - [`PortalInput`](portal::PortalInput)
 //!
 //! # Usage
@@ -18,18 +19,36 @@
 //! use leptos::*;
 //! use leptos_declarative::prelude::*;
 //!
-//! # let _ = create_scope(create_runtime(), |cx| {
-//! let (a, _) = create_signal(cx, true);
-//! let (b, _) = create_signal(cx, false);
+//! # let runtime = create_runtime();
+//! let (a, _) = create_signal(true);
+//! let (b, _) = create_signal(false);
 //!
-//! view! { cx,
+//! view! {
 //! <If signal=a>
 //!   <Then>"A is true!"</Then>
 //!   <ElseIf signal=b>"B is true!"</ElseIf>
 //!   <Else>"Both A and B are false!"</Else>
 //! </If>
 //! };
-//! # });
+//! # runtime.dispose();
+//! ```
+//!
+//! ## Match
+//! ```rust
+//! use leptos::*;
+//! use leptos_declarative::prelude::*;
+//!
+//! # let runtime = create_runtime();
+//! let (fruit, _) = create_signal("apple");
+//!
+//! view! {
+//! <Match signal=fruit>
+//!   <Arm value="apple">"An apple a day..."</Arm>
+//!   <Arm value="orange">"Orange you glad?"</Arm>
+//!   <Fallback>"Some other fruit."</Fallback>
+//! </Match>
+//! };
+//! # runtime.dispose();
 //! ```
 //!
 //! ## Portal
@@ -37,11 +56,11 @@
 //! use leptos::*;
 //! use leptos_declarative::prelude::*;
 //!
-//! # let _ = create_scope(create_runtime(), |cx| {
+//! # let runtime = create_runtime();
 //!
 //! struct PortalId;
 //!
-//! view! { cx,
+//! view! {
 //!   <PortalProvider>
 //!     <div>
 //!       <h1>"Portal goes here!"</h1>
@@ -53,18 +72,114 @@
 //!     </PortalInput>
 //!   </PortalProvider>
 //! };
-//! # });
+//! # runtime.dispose();
+//! ```
+//!
+//! ## Components made of a single construct
+//!
+//! Every component of this crate returns `impl IntoView`, so a component's
+//! whole body can be one of them, returned as is, without wrapping it in an
+//! element or a closure.
+//! ```rust
+//! use leptos::*;
+//! use leptos_declarative::prelude::*;
+//!
+//! struct Toolbar;
+//!
+//! #[component]
+//! fn Greeting(#[prop(into)] user: MaybeSignal<Option<String>>) -> impl IntoView {
+//!   view! {
+//!   <If signal=move || user.get()>
+//!     <Then>"Welcome back!"</Then>
+//!     <Else>"Please log in."</Else>
+//!   </If>
+//!   }
+//! }
+//!
+//! #[component]
+//! fn Fruit(#[prop(into)] fruit: MaybeSignal<&'static str>) -> impl IntoView {
+//!   view! {
+//!   <Match signal=fruit>
+//!     <Arm value="apple">"An apple a day..."</Arm>
+//!     <Fallback>"Some other fruit."</Fallback>
+//!   </Match>
+//!   }
+//! }
+//!
+//! #[component]
+//! fn ToolbarSlot() -> impl IntoView {
+//!   view! { <PortalOutput id=Toolbar /> }
+//! }
+//!
+//! let html = leptos::ssr::render_to_string(|| {
+//!   view! {
+//!   <PortalProvider>
+//!     <PortalInput id=Toolbar>"Export"</PortalInput>
+//!     <ToolbarSlot />
+//!     <Greeting user=None />
+//!     <Fruit fruit="apple" />
+//!   </PortalProvider>
+//!   }
+//! });
+//!
+//! assert!(html.contains("Export"));
+//! assert!(html.contains("Please log in."));
+//! assert!(html.contains("An apple a day..."));
 //! ```
 
 #[macro_use]
-mod util;
+pub mod util;
+pub mod anchor;
+pub mod async_;
+#[cfg(feature = "broadcast")]
+pub mod broadcast;
+pub mod class;
+pub mod condition;
+pub mod experiment;
+pub mod filter;
 pub mod if_;
+pub mod list;
+pub mod match_;
+pub mod maybe;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod motion;
+pub mod once;
 pub mod portal;
+#[cfg(feature = "router")]
+pub mod route;
+#[cfg(feature = "serde")]
+pub mod rule;
+pub mod when;
 
 /// Convenient import of all components.
 pub mod prelude {
   pub use crate::{
+    anchor::*,
+    async_::*,
+    cfg_branch,
+    class::*,
+    condition::*,
+    experiment::*,
+    filter::*,
     if_::*,
+    list::*,
+    match_::*,
+    match_key,
+    match_view,
+    maybe::*,
+    motion::*,
+    once::*,
     portal::*,
+    portal_scope,
+    shared_condition,
+    when::*,
   };
+
+  #[cfg(feature = "broadcast")]
+  pub use crate::broadcast::*;
+  #[cfg(feature = "router")]
+  pub use crate::route::*;
+  #[cfg(feature = "serde")]
+  pub use crate::rule::*;
 }
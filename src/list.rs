@@ -0,0 +1,225 @@
+//! Keyed lists for [`leptos`].
+//!
+//! Please refer to [`ForIndexed`] and [`GroupBy`] for usage examples.
+
+use leptos::*;
+use std::{
+  collections::HashMap,
+  hash::Hash,
+  rc::Rc,
+};
+
+/// Iterates over a keyed list like [`For`], but also gives each item's view
+/// its index in the list, as a signal which updates when the item moves.
+///
+/// Unlike an index captured when the item's view is built, the index stays
+/// correct when items are inserted, removed or reordered, while the views
+/// themselves are kept as they are, like with [`For`].
+///
+/// This has a cost over [`For`]: `each` is called twice per change, keys are
+/// computed for every item on every change, and each item holds a memo of
+/// its index. Prefer [`For`] when the index is not needed.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (players, set_players) = create_signal(vec![(0, "Alice"), (1, "Bob")]);
+///
+/// view! {
+///   <ol>
+///     <ForIndexed
+///       each=move || players.get()
+///       key=|(id, _)| *id
+///       children={|index: Signal<usize>, (_, name)| {
+///         view! { <li>"#" {move || index.get() + 1} " " {name}</li> }
+///       }}
+///     />
+///   </ol>
+/// };
+///
+/// // Bob's view is kept, and now shows "#1"
+/// set_players.update(|players| players.reverse());
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn ForIndexed<IF, I, T, EF, N, KF, K>(
+  /// Items over which the component should iterate.
+  each: IF,
+  /// A key function that will be applied to each item.
+  key: KF,
+  /// The view that will be displayed for each item, given the index of the
+  /// item and the item.
+  children: EF,
+) -> impl IntoView
+where
+  IF: Fn() -> I + 'static,
+  I: IntoIterator<Item = T>,
+  EF: Fn(Signal<usize>, T) -> N + 'static,
+  N: IntoView + 'static,
+  KF: Fn(&T) -> K + 'static,
+  K: Eq + Hash + Clone + 'static,
+  T: 'static,
+{
+  let each = Rc::new(each);
+  let key = Rc::new(key);
+
+  let positions = create_memo({
+    let each = each.clone();
+    let key = key.clone();
+
+    move |_| {
+      each()
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| (key(&item), i))
+        .collect::<HashMap<_, _>>()
+    }
+  });
+
+  let item_view = {
+    let key = key.clone();
+
+    move |item: T| {
+      let item_key = key(&item);
+
+      let index = create_memo(move |_| {
+        positions
+          .with(|positions| positions.get(&item_key).copied())
+          .unwrap_or_default()
+      });
+
+      children(index.into(), item)
+    }
+  };
+
+  view! {
+    <For
+      each=move || each()
+      key=move |item: &T| key(item)
+      children=item_view
+    />
+  }
+}
+
+/// Partitions a list into groups by the key `group_by` gives each item, and
+/// renders each group, e.g. with a header followed by its items.
+///
+/// Groups are rendered in the order in which their first item appears in the
+/// list, and each group's items keep their order from the list. Groups are
+/// keyed by their key, like items of a [`For`]: the view of a group is built
+/// once, when the group first appears, and is given its items as a signal
+/// which updates as items are added to or removed from the group. Once a
+/// group has no items left, its view is removed.
+///
+/// An item which moves to another group leaves the items of its old group and
+/// joins those of the new one. Rendering the items of a group with a [`For`]
+/// therefore removes the item's view from the old group, and builds a new one
+/// in the new group, while the other items of both groups keep their views.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[derive(Clone, PartialEq)]
+/// struct Task {
+///   id: u32,
+///   title: &'static str,
+///   done: bool,
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (tasks, set_tasks) = create_signal(vec![
+///   Task { id: 0, title: "Write docs", done: false },
+///   Task { id: 1, title: "Fix bug", done: true },
+/// ]);
+///
+/// view! {
+///   <GroupBy
+///     each=move || tasks.get()
+///     group_by=|task: &Task| task.done
+///     children={|done: bool, tasks: Signal<Vec<Task>>| view! {
+///       <h2>{if done { "Done" } else { "To do" }}</h2>
+///       <ul>
+///         <For
+///           each=move || tasks.get()
+///           key=|task| task.id
+///           children=|task| view! { <li>{task.title}</li> }
+///         />
+///       </ul>
+///     }}
+///   />
+/// };
+///
+/// // "Write docs" moves from the "To do" group to the "Done" one
+/// set_tasks.update(|tasks| tasks[0].done = true);
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn GroupBy<IF, I, T, GF, G, EF, N>(
+  /// Items which should be grouped.
+  each: IF,
+  /// The key of the group of an item.
+  group_by: GF,
+  /// The view that will be displayed for each group, given the key of the
+  /// group and its items.
+  children: EF,
+) -> impl IntoView
+where
+  IF: Fn() -> I + 'static,
+  I: IntoIterator<Item = T>,
+  GF: Fn(&T) -> G + 'static,
+  G: Eq + Hash + Clone + 'static,
+  EF: Fn(G, Signal<Vec<T>>) -> N + 'static,
+  N: IntoView + 'static,
+  T: Clone + PartialEq + 'static,
+{
+  let groups = create_memo(move |_| {
+    let mut groups = Vec::<(G, Vec<T>)>::new();
+
+    for item in each() {
+      let group = group_by(&item);
+
+      if let Some((_, items)) = groups.iter_mut().find(|(g, _)| *g == group) {
+        items.push(item);
+      } else {
+        groups.push((group, vec![item]));
+      }
+    }
+
+    groups
+  });
+
+  let group_view = move |group: G| {
+    let items = create_memo({
+      let group = group.clone();
+
+      move |_| {
+        groups.with(|groups| {
+          groups
+            .iter()
+            .find(|(g, _)| *g == group)
+            .map(|(_, items)| items.clone())
+            .unwrap_or_default()
+        })
+      }
+    });
+
+    children(group, items.into())
+  };
+
+  view! {
+    <For
+      each=move || {
+        groups.with(|groups| {
+          groups.iter().map(|(group, _)| group.clone()).collect::<Vec<_>>()
+        })
+      }
+      key=|group: &G| group.clone()
+      children=group_view
+    />
+  }
+}
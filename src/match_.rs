@@ -0,0 +1,1167 @@
+//! The `match` construct for [`leptos`].
+//!
+//! Please refer to [`Match`] for usage examples.
+
+use crate::{
+  experiment::{
+    pick_weighted,
+    use_experiment_seed,
+  },
+  util::{
+    transparent_children,
+    MetricsRecorder,
+  },
+};
+use leptos::*;
+use leptos_dom::Transparent;
+use std::{
+  cell::RefCell,
+  ops,
+  rc::Rc,
+};
+
+pub use leptos_declarative_macros::MatchKey;
+
+/// The `match` construct in component form.
+///
+/// Renders the first [`Arm`] whose `value` equals the [`Match`]'s signal, or
+/// [`RangeArm`] whose `range` contains it, or the [`Fallback`] if no arm
+/// matches.
+///
+/// For more docs on allowed child components, check out
+/// [`MatchProps::children`].
+///
+/// # Examples
+///
+/// ### Simple `match`
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (fruit, _) = create_signal("apple");
+///
+/// view! {
+/// <Match signal=fruit>
+///   <Arm value="apple">"An apple a day..."</Arm>
+///   <Arm value="orange">"Orange you glad?"</Arm>
+///   <Fallback>"Some other fruit."</Fallback>
+/// </Match>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Matching ranges
+///
+/// Arms are checked in order and the first one to match wins, so
+/// overlapping ranges are allowed, with earlier arms taking precedence.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// fn render(progress: i32) -> String {
+///   leptos::ssr::render_to_string(move || {
+///     view! {
+///     <Match signal=progress>
+///       <Arm value=100>"Done!"</Arm>
+///       <RangeArm range={0..=33}>"Low"</RangeArm>
+///       <RangeArm range={34..=66}>"Medium"</RangeArm>
+///       <RangeArm range={67..}>"High"</RangeArm>
+///       <Fallback>"Invalid"</Fallback>
+///     </Match>
+///     }
+///   })
+///   .to_string()
+/// }
+///
+/// assert!(render(20).contains("Low"));
+/// assert!(render(50).contains("Medium"));
+/// assert!(render(80).contains("High"));
+/// assert!(render(100).contains("Done!"));
+/// assert!(render(-1).contains("Invalid"));
+/// ```
+///
+/// ### Evaluation
+///
+/// The arms, and therefore their `value`s and `range`s, are built once, when
+/// the [`Match`] is created, not whenever the signal changes. Matching then
+/// compares the signal against them in order and stops at the first arm
+/// which matches: only the children of that arm are built, and those of the
+/// other arms are never called.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::sync::atomic::{
+///   AtomicBool,
+///   Ordering,
+/// };
+///
+/// static BUILT: AtomicBool = AtomicBool::new(false);
+///
+/// #[component]
+/// fn Expensive() -> impl IntoView {
+///   BUILT.store(true, Ordering::Relaxed);
+///
+///   "Expensive"
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <Match signal=1>
+///     <Arm value=1>"Cheap"</Arm>
+///     <Arm value=1><Expensive /></Arm>
+///     <Fallback><Expensive /></Fallback>
+///   </Match>
+///   }
+/// });
+///
+/// assert!(html.contains("Cheap"));
+/// assert!(!BUILT.load(Ordering::Relaxed));
+/// ```
+///
+/// With [`MatchStrategy::Weighted`], every arm is compared, as any of them
+/// may be picked, but only the children of the picked arm are built.
+///
+/// ### Weighted arms
+///
+/// With `strategy=MatchStrategy::Weighted`, when several arms match, one of
+/// them is picked at random, in proportion to its `weight`, e.g. to render
+/// the variants of an A/B experiment. The pick is stable for a given seed,
+/// please refer to the [`experiment`](crate::experiment) module.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   // Usually stored in the session
+///   provide_experiment_seed(7);
+///
+///   view! {
+///   <Match signal="checkout" strategy=MatchStrategy::Weighted>
+///     <Arm value="checkout" weight=1>"Pay now"</Arm>
+///     <Arm value="checkout" weight=1>"Complete purchase"</Arm>
+///     <Fallback>"Keep shopping"</Fallback>
+///   </Match>
+///   }
+/// });
+///
+/// assert!(html.contains("Pay now") || html.contains("Complete purchase"));
+/// assert!(!html.contains("Keep shopping"));
+/// ```
+///
+/// ### Matching enum variants
+///
+/// Enums whose variants carry data can derive [`MatchKey`](derive@MatchKey)
+/// to get a fieldless key per variant, which can then be matched on without
+/// building a full value for every [`Arm`].
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[derive(Clone, MatchKey)]
+/// enum Status {
+///   Loading,
+///   Ready(String),
+///   Failed { error: String },
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (status, _) = create_signal(Status::Ready("Alice".to_string()));
+///
+/// let status_key = Signal::derive(move || status.with(MatchKey::key));
+///
+/// view! {
+/// <Match signal=status_key>
+///   <Arm value=StatusKey::Loading>"Loading..."</Arm>
+///   <Arm value=StatusKey::Ready>"Ready!"</Arm>
+///   <Arm value=StatusKey::Failed>"Something went wrong."</Arm>
+/// </Match>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn Match<S>(
+  /// The value to match, please refer to [`IntoMatchValue`] for what it can
+  /// be.
+  signal: S,
+  /// The arms you would like to match against.
+  ///
+  /// Children must be any
+  /// - [`Arm`]
+  /// - [`ArmWith`]
+  /// - [`RangeArm`]
+  /// - [`Fallback`]
+  ///
+  /// Any other child not in the above list will not be rendered.
+  ///
+  /// Arms are checked in order, and the first one to match is rendered.
+  ///
+  /// [`Fallback`] must be the last child.
+  children: Box<dyn Fn() -> Fragment>,
+  /// How the arm to render is chosen when several of them match.
+  #[prop(optional)]
+  strategy: MatchStrategy,
+) -> impl IntoView
+where
+  S: IntoMatchValue,
+  S::Value: PartialEq + Clone,
+{
+  // Memoize the signal
+  let signal = signal.into_signal();
+  let signal = create_memo(move |_| signal.get());
+
+  let metrics = MetricsRecorder::new("Match", None);
+
+  let children = children();
+
+  // Get the arms, which carry several payload types
+  let arms = transparent_children(&children)
+    .cloned()
+    .collect::<Rc<[_]>>();
+
+  #[cfg(debug_assertions)]
+  run_debug_checks::<S::Value>(&arms);
+
+  // The arm is only rendered again when another one matches, not whenever
+  // the value changes
+  let active_arm = create_memo({
+    let arms = arms.clone();
+    let metrics = metrics.clone();
+
+    move |_| {
+      metrics.evaluation();
+
+      signal.with(|value| select_arm(&arms, value, strategy))
+    }
+  });
+
+  move || {
+    let Some(i) = active_arm.get() else {
+      return ().into_view();
+    };
+
+    let arm = arms[i].clone();
+
+    // A binding arm is rendered again whenever the value changes, as it
+    // receives the value itself
+    let value = arm
+      .downcast_ref::<MatchBindingArm<S::Value>>()
+      .map(|_| signal.get());
+
+    metrics.render();
+
+    render_arm_scoped(arm, value, signal)
+  }
+}
+
+/// Returns the position of the arm of `arms` to render for `value`, if any.
+fn select_arm<T>(
+  arms: &[Transparent],
+  value: &T,
+  strategy: MatchStrategy,
+) -> Option<usize>
+where
+  T: PartialEq + 'static,
+{
+  let mut matching = arms
+    .iter()
+    .enumerate()
+    .filter_map(|(i, arm)| match_arm(arm, value).map(|weight| (i, weight)));
+
+  match strategy {
+    // Arms are compared in order, only until the first one which matches
+    MatchStrategy::FirstMatch => matching.next().map(|(i, _)| i),
+    MatchStrategy::Weighted => {
+      let matching = matching.collect::<Vec<_>>();
+
+      let candidates = matching
+        .iter()
+        .filter_map(|(i, weight)| weight.map(|weight| (*i, weight)))
+        .collect::<Vec<_>>();
+
+      // The fallback, without a weight, only when no arm matches
+      let picked = pick_weighted(use_experiment_seed(), &candidates);
+
+      matching
+        .into_iter()
+        .find(|(i, weight)| {
+          picked.map_or(weight.is_none(), |picked| *i == picked)
+        })
+        .map(|(i, _)| i)
+    }
+  }
+}
+
+/// Renders `arm` under an owner of its own, so that the signals and effects
+/// of its children, and any context they provide, are removed along with
+/// it. `value` is the value a binding arm is rendered with.
+fn render_arm_scoped<T>(
+  arm: Transparent,
+  value: Option<T>,
+  signal: Memo<T>,
+) -> View
+where
+  T: Clone + 'static,
+{
+  let (view, disposer) = as_child_of_current_owner(move |value: Option<T>| {
+    provide_context(Matched(signal));
+
+    match arm_children::<T>(&arm) {
+      Some(ArmChildren::Plain(children)) => children().into_view(),
+      Some(ArmChildren::Binding(children)) => {
+        children(value.unwrap_or_else(|| signal.get_untracked()))
+      }
+      None => ().into_view(),
+    }
+  })(value);
+
+  on_cleanup(move || drop(disposer));
+
+  view
+}
+
+/// Returns, if `arm` matches `value`, its weight under
+/// [`MatchStrategy::Weighted`], the weight being [`None`] for the
+/// [`Fallback`], which always matches.
+fn match_arm<T>(arm: &Transparent, value: &T) -> Option<Option<u32>>
+where
+  T: PartialEq + 'static,
+{
+  if let Some(arm) = arm.downcast_ref::<MatchArm<T>>() {
+    arm.values.contains(value).then_some(Some(arm.weight))
+  } else if let Some(arm) = arm.downcast_ref::<MatchBindingArm<T>>() {
+    arm.values.contains(value).then_some(Some(1))
+  } else if let Some(arm) = arm.downcast_ref::<MatchRangeArm<T>>() {
+    (arm.contains)(value).then_some(Some(1))
+  } else {
+    arm.downcast_ref::<MatchFallback>().map(|_| None)
+  }
+}
+
+/// Returns the children of `arm`, if it is an arm of a [`Match`] over `T`.
+fn arm_children<T: 'static>(arm: &Transparent) -> Option<ArmChildren<'_, T>> {
+  if let Some(arm) = arm.downcast_ref::<MatchArm<T>>() {
+    Some(ArmChildren::Plain(&*arm.children))
+  } else if let Some(arm) = arm.downcast_ref::<MatchBindingArm<T>>() {
+    Some(ArmChildren::Binding(&*arm.children))
+  } else if let Some(arm) = arm.downcast_ref::<MatchRangeArm<T>>() {
+    Some(ArmChildren::Plain(&*arm.children))
+  } else {
+    arm
+      .downcast_ref::<MatchFallback>()
+      .map(|fallback| ArmChildren::Plain(&*fallback.children))
+  }
+}
+
+/// How [`Match`] chooses the arm to render when several of them match.
+///
+/// The [`Fallback`] is only rendered when no arm matches, whichever the
+/// strategy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MatchStrategy {
+  /// The first arm which matches, in declaration order, is rendered.
+  #[default]
+  FirstMatch,
+  /// One of the arms which match is picked at random, in proportion to the
+  /// `weight` of each [`Arm`], other arms having a weight of `1`, with the
+  /// experiment seed in context. Please refer to the
+  /// [`experiment`](crate::experiment) module for how the seed makes the
+  /// pick reproducible.
+  Weighted,
+}
+
+/// Like [`Match`], but matches on a key computed from the signal by `key`
+/// rather than on the signal itself.
+///
+/// The children are the same as those of [`Match`], with [`Arm`] values and
+/// [`RangeArm`] ranges being keys rather than values. This allows matching on
+/// a projection of state which is not comparable as a whole, e.g. the role of
+/// a user.
+///
+/// The key is memoized, so an arm is only rendered again when the key changes,
+/// not when any other part of the value does. Likewise, the children of the
+/// rendered arm receive the key as [`Matched`] context.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Role {
+///   name: String,
+/// }
+///
+/// #[derive(Clone)]
+/// struct User {
+///   name: String,
+///   role: Role,
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   let (user, _) = create_signal(User {
+///     name: "Alice".to_string(),
+///     role: Role {
+///       name: "admin".to_string(),
+///     },
+///   });
+///
+///   view! {
+///   <MatchBy signal=user key=|user: &User| user.role.name.clone()>
+///     <Arm value={"admin".to_string()}>"Admin tools"</Arm>
+///     <Arm value={"editor".to_string()}>"Editor tools"</Arm>
+///     <Fallback>"Nothing to manage."</Fallback>
+///   </MatchBy>
+///   }
+/// });
+///
+/// assert!(html.contains("Admin tools"));
+/// ```
+#[component]
+pub fn MatchBy<T, K, F>(
+  /// The value to compute the key of.
+  #[prop(into)]
+  signal: MaybeSignal<T>,
+  /// Computes the key to match from the value.
+  key: F,
+  /// The arms you would like to match the key against, as with
+  /// [`MatchProps::children`].
+  children: Box<dyn Fn() -> Fragment>,
+) -> impl IntoView
+where
+  T: 'static,
+  K: PartialEq + Clone + 'static,
+  F: Fn(&T) -> K + 'static,
+{
+  let key = create_memo(move |_| signal.with(&key));
+
+  Match(MatchProps::builder().signal(key).children(children).build())
+}
+
+/// Like [`Match`], but with arms built from data by `arms`, rather than
+/// declared as children, e.g. for tabs loaded from the server.
+///
+/// `arms` returns each arm as a key and its children, and is reactive over
+/// any signal it reads, so arms can be added and removed at runtime. The arm
+/// whose key equals the signal is rendered, the first one if several do, or
+/// `fallback` if none does.
+///
+/// Arms are told apart by their key: as long as the key matching the signal
+/// stays among the arms, its children are kept as they are, DOM and state
+/// included, however the other arms change. They are only built again once
+/// another arm was rendered in between, or once the arm was removed and added
+/// back.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   let (tabs, _) = create_signal(vec![
+///     ("inbox".to_string(), "Your messages".to_string()),
+///     ("drafts".to_string(), "Unsent messages".to_string()),
+///   ]);
+///   let (active_tab, _) = create_signal("drafts".to_string());
+///
+///   view! {
+///   <MatchDynamic
+///     signal=active_tab
+///     arms=move || {
+///       tabs
+///         .get()
+///         .into_iter()
+///         .map(|(key, content)| {
+///           let children: Children =
+///             Box::new(move || Fragment::new(vec![content.into_view()]));
+///
+///           (key, children)
+///         })
+///         .collect()
+///     }
+///     fallback=|| "No such tab."
+///   />
+///   }
+/// });
+///
+/// assert!(html.contains("Unsent messages"));
+/// assert!(!html.contains("Your messages"));
+/// ```
+#[component]
+pub fn MatchDynamic<K, F>(
+  /// The key of the arm to render.
+  #[prop(into)]
+  signal: MaybeSignal<K>,
+  /// Builds the arms, as their keys and children.
+  arms: F,
+  /// What to show when no arm has the key of the signal.
+  #[prop(optional, into)]
+  fallback: Option<ViewFn>,
+) -> impl IntoView
+where
+  K: PartialEq + Clone + 'static,
+  F: Fn() -> Vec<(K, Children)> + 'static,
+{
+  // Memoize the signal
+  let signal = create_memo(move |_| signal.get());
+
+  let metrics = MetricsRecorder::new("MatchDynamic", None);
+
+  // Arms are kept across runs of the closure below, so they must belong to
+  // the component itself, or they would be disposed of with the closure
+  let owner = Owner::current();
+
+  let build_arm = move |children: Children| {
+    let render = || {
+      as_child_of_current_owner(|children: Children| {
+        provide_context(Matched(signal));
+
+        children().into_view()
+      })(children)
+    };
+
+    match owner {
+      Some(owner) => with_owner(owner, render),
+      None => render(),
+    }
+  };
+
+  let rendered_arm = RefCell::new(None::<(K, View, _)>);
+
+  move || {
+    metrics.evaluation();
+
+    let arms = arms();
+
+    signal.with(|key| {
+      let matched_arm = arms.into_iter().find(|(k, _)| k == key);
+
+      let mut rendered_arm = rendered_arm.borrow_mut();
+
+      match matched_arm {
+        Some((_, children)) => {
+          let is_rendered = matches!(&*rendered_arm, Some((k, ..)) if k == key);
+
+          if !is_rendered {
+            metrics.render();
+
+            let (view, disposer) = build_arm(children);
+
+            *rendered_arm = Some((key.clone(), view, disposer));
+          }
+
+          rendered_arm
+            .as_ref()
+            .map(|(_, view, _)| view.clone())
+            .into_view()
+        }
+        None => {
+          *rendered_arm = None;
+
+          fallback.as_ref().map(ViewFn::run).into_view()
+        }
+      }
+    })
+  }
+}
+
+/// Returns which of `keys` the value of `signal` currently matches, without
+/// rendering anything, e.g. to drive side effects off the active arm of a
+/// [`Match`].
+///
+/// Keys are compared to the value like [`Arm`] values: they are checked in
+/// order, and the first one equal to the value is returned. When none is,
+/// the memo holds [`None`], which is when a [`Match`] with the same arms
+/// would render its [`Fallback`].
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (page, set_page) = create_signal("settings".to_string());
+///
+/// let active = use_match(page, ["home", "settings"]);
+///
+/// assert_eq!(active.get(), Some("settings"));
+///
+/// set_page.set("about".to_string());
+///
+/// assert_eq!(active.get(), None);
+/// # runtime.dispose();
+/// ```
+pub fn use_match<T, K>(
+  signal: impl IntoMatchValue<Value = T>,
+  keys: impl IntoIterator<Item = K>,
+) -> Memo<Option<K>>
+where
+  T: Clone + 'static,
+  K: PartialEq + PartialEq<T> + Clone + 'static,
+{
+  let signal = signal.into_signal();
+  let keys = keys.into_iter().collect::<Vec<_>>();
+
+  create_memo(move |_| {
+    signal.with(|value| keys.iter().find(|key| *key == value).cloned())
+  })
+}
+
+/// This must be the direct child of a [`Match`] component. It will render its
+/// children iff the [`Match`] signal equals `value`, or any of `values`, and
+/// no previous [`Arm`] matched.
+///
+/// `values` is the equivalent of an `A | B` pattern, so that several values
+/// share one arm rather than repeating its children.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <Match signal="saturday">
+///     <Arm values=vec!["saturday", "sunday"]>"Weekend!"</Arm>
+///     <Fallback>"Weekday."</Fallback>
+///   </Match>
+///   }
+/// });
+///
+/// assert!(html.contains("Weekend!"));
+/// ```
+#[component(transparent)]
+pub fn Arm<T>(
+  /// The value the [`Match`] signal must equal for this arm to be rendered.
+  #[prop(optional)]
+  value: Option<T>,
+  /// Values the [`Match`] signal may equal any of for this arm to be
+  /// rendered, in addition to `value`.
+  #[prop(optional)]
+  values: Vec<T>,
+  /// With [`MatchStrategy::Weighted`], how likely this arm is to be picked
+  /// among the arms which match, relative to their weights. A weight of `0`
+  /// is never picked while another matching arm has a weight. Defaults to
+  /// `1`.
+  #[prop(optional)]
+  weight: Option<u32>,
+  /// What you want to show when this arm matches.
+  children: Box<dyn Fn() -> Fragment>,
+) -> impl IntoView
+where
+  T: 'static,
+{
+  #[cfg(debug_assertions)]
+  assert!(
+    value.is_some() || !values.is_empty(),
+    "`<Arm />` must have a `value` or `values`, otherwise it never matches"
+  );
+
+  MatchArm {
+    values: value.into_iter().chain(values).collect(),
+    weight: weight.unwrap_or(1),
+    children,
+  }
+}
+
+/// Like [`Arm`], but its children are given the matched value, bound with
+/// `let:`, e.g. to render the exact value among several `values`, or data
+/// the value carries which equality does not look at.
+///
+/// As with [`maybe`](crate::maybe::maybe), the children are given the value
+/// itself, and are rendered again whenever it changes while the arm still
+/// matches.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <Match signal="sunday">
+///     <ArmWith values=vec!["saturday", "sunday"] let:day>
+///       "Enjoy your " {day} "!"
+///     </ArmWith>
+///     <Fallback>"Weekday."</Fallback>
+///   </Match>
+///   }
+/// });
+///
+/// assert!(html.contains("Enjoy your "));
+/// assert!(html.contains("sunday"));
+/// ```
+#[component(transparent)]
+pub fn ArmWith<T, F, IV>(
+  /// The value the [`Match`] signal must equal for this arm to be rendered.
+  #[prop(optional)]
+  value: Option<T>,
+  /// Values the [`Match`] signal may equal any of for this arm to be
+  /// rendered, in addition to `value`.
+  #[prop(optional)]
+  values: Vec<T>,
+  /// What you want to show when this arm matches, given the matched value.
+  children: F,
+) -> impl IntoView
+where
+  T: 'static,
+  F: Fn(T) -> IV + 'static,
+  IV: IntoView,
+{
+  #[cfg(debug_assertions)]
+  assert!(
+    value.is_some() || !values.is_empty(),
+    "`<ArmWith />` must have a `value` or `values`, otherwise it never matches"
+  );
+
+  MatchBindingArm {
+    values: value.into_iter().chain(values).collect(),
+    children: Box::new(move |value| children(value).into_view()),
+  }
+}
+
+/// This must be the direct child of a [`Match`] component. It will render its
+/// children iff `range` contains the [`Match`] signal and no previous arm
+/// matched.
+///
+/// The range must be of the same type as the [`Match`] signal, otherwise it
+/// is ignored.
+#[component(transparent)]
+pub fn RangeArm<T>(
+  /// The range which must contain the [`Match`] signal for this arm to be
+  /// rendered, such as `0..=33` or `67..`.
+  #[prop(into)]
+  range: ArmRange<T>,
+  /// What you want to show when this arm matches.
+  children: Box<dyn Fn() -> Fragment>,
+) -> impl IntoView
+where
+  T: 'static,
+{
+  MatchRangeArm {
+    contains: range.0,
+    children,
+  }
+}
+
+/// The range of a [`RangeArm`], converted from any range over the type of the
+/// [`Match`] signal, such as `0..=33` or `67..`.
+pub struct ArmRange<T>(Box<dyn Fn(&T) -> bool>);
+
+macro_rules! impl_from_range_for_arm_range {
+  ($($range:ident),*) => {
+    $(
+      impl<T> From<ops::$range<T>> for ArmRange<T>
+      where
+        T: PartialOrd + 'static,
+      {
+        fn from(range: ops::$range<T>) -> Self {
+          Self(Box::new(move |value| range.contains(value)))
+        }
+      }
+    )*
+  };
+}
+
+impl_from_range_for_arm_range!(
+  Range,
+  RangeInclusive,
+  RangeFrom,
+  RangeTo,
+  RangeToInclusive
+);
+
+/// This must be the direct child of a [`Match`] component, and be the last
+/// component. It will render its children iff no [`Arm`] matched.
+#[component(transparent)]
+pub fn Fallback(
+  /// What you want to show when no arm matches.
+  children: Box<dyn Fn() -> Fragment>,
+) -> impl IntoView {
+  MatchFallback { children }
+}
+
+/// The [`Match`] signal, provided as context to the children of whichever arm
+/// is rendered, so that they can read the matched value without having the
+/// signal passed down to them.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[component]
+/// fn Score() -> impl IntoView {
+///   let Matched(score) = expect_context::<Matched<i32>>();
+///
+///   view! { <p>"You scored " {score} " points."</p> }
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <Match signal=42>
+///     <RangeArm range={0..50}><Score /></RangeArm>
+///     <Fallback>"Great score!"</Fallback>
+///   </Match>
+///   }
+/// });
+///
+/// assert!(html.contains("42"));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Matched<T: 'static>(pub Memo<T>);
+
+/// Represents a match arm, which is returned by [`Arm`].
+pub struct MatchArm<T> {
+  /// The values the [`Match`] signal must equal any of for this arm to be
+  /// rendered.
+  pub values: Vec<T>,
+  /// The weight of this arm under [`MatchStrategy::Weighted`].
+  pub weight: u32,
+  /// The children method.
+  pub children: Box<dyn Fn() -> Fragment>,
+}
+
+impl<T> IntoView for MatchArm<T>
+where
+  T: 'static,
+{
+  fn into_view(self) -> View {
+    View::Transparent(Transparent::new(self))
+  }
+}
+
+/// Represents a match arm binding the matched value, which is returned by
+/// [`ArmWith`].
+pub struct MatchBindingArm<T> {
+  /// The values the [`Match`] signal must equal any of for this arm to be
+  /// rendered.
+  pub values: Vec<T>,
+  /// The children method, given the matched value.
+  pub children: Box<dyn Fn(T) -> View>,
+}
+
+impl<T> IntoView for MatchBindingArm<T>
+where
+  T: 'static,
+{
+  fn into_view(self) -> View {
+    View::Transparent(Transparent::new(self))
+  }
+}
+
+/// The children of an arm of a [`Match`].
+enum ArmChildren<'a, T> {
+  Plain(&'a dyn Fn() -> Fragment),
+  Binding(&'a dyn Fn(T) -> View),
+}
+
+/// Represents a match arm over a range, which is returned by [`RangeArm`].
+pub struct MatchRangeArm<T> {
+  /// Whether the range contains the [`Match`] signal.
+  pub contains: Box<dyn Fn(&T) -> bool>,
+  /// The children method.
+  pub children: Box<dyn Fn() -> Fragment>,
+}
+
+impl<T> IntoView for MatchRangeArm<T>
+where
+  T: 'static,
+{
+  fn into_view(self) -> View {
+    View::Transparent(Transparent::new(self))
+  }
+}
+
+/// Represents the fallback of a [`Match`], which is returned by [`Fallback`].
+pub struct MatchFallback {
+  /// The children method.
+  pub children: Box<dyn Fn() -> Fragment>,
+}
+
+impl IntoView for MatchFallback {
+  fn into_view(self) -> View {
+    View::Transparent(Transparent::new(self))
+  }
+}
+
+/// Like [`Match`] over the [`MatchKey`] of `signal`, but the compiler checks
+/// that every variant has an arm, just like a `match` expression.
+///
+/// Each arm maps a key to what to render for it. A variant can be skipped
+/// explicitly by rendering `()`. As with [`Match`], an arm is only rendered
+/// again when the variant changes, not when the data inside it does.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[derive(Clone, MatchKey)]
+/// enum Status {
+///   Loading,
+///   Ready(String),
+///   Failed { error: String },
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (status, _) = create_signal(Status::Loading);
+///
+/// view! {
+///   <div>
+///     {match_key! {
+///       status,
+///       StatusKey::Loading => "Loading...",
+///       StatusKey::Ready => view! { <p>"Ready!"</p> },
+///       StatusKey::Failed => (),
+///     }}
+///   </div>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// Forgetting a variant fails to compile:
+/// ```rust,compile_fail
+/// # use leptos::*;
+/// # use leptos_declarative::prelude::*;
+/// #
+/// # #[derive(Clone, MatchKey)]
+/// # enum Status {
+/// #   Loading,
+/// #   Ready(String),
+/// #   Failed { error: String },
+/// # }
+/// #
+/// # let runtime = create_runtime();
+/// # let (status, _) = create_signal(Status::Loading);
+/// match_key! {
+///   status,
+///   StatusKey::Loading => "Loading...",
+///   StatusKey::Ready => "Ready!",
+/// };
+/// # runtime.dispose();
+/// ```
+#[macro_export]
+macro_rules! match_key {
+  ($signal:expr, $($key:path => $view:expr),+ $(,)?) => {{
+    let signal = $signal;
+    let key = ::leptos::create_memo(move |_| {
+      ::leptos::SignalWith::with(&signal, $crate::match_::MatchKey::key)
+    });
+
+    move || match ::leptos::SignalGet::get(&key) {
+      $(
+        $key => ::leptos::IntoView::into_view($view),
+      )+
+    }
+  }};
+}
+
+/// Builds a [`Match`] with `match`-like syntax, which is terser than writing
+/// out an [`Arm`] per value.
+///
+/// Each `value => view` pair becomes an [`Arm`], and a final `_ => view` the
+/// [`Fallback`]. The result is the very same [`Match`] as if written with
+/// components, so the arms behave identically, and the views of the arms can
+/// use [`Matched`] as usual.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// fn render(fruit: &'static str) -> String {
+///   leptos::ssr::render_to_string(move || {
+///     match_view! { fruit => {
+///       "apple" => view! { <p>"An apple a day..."</p> },
+///       "orange" => "Orange you glad?",
+///       _ => "Some other fruit.",
+///     }}
+///   })
+///   .to_string()
+/// }
+///
+/// assert!(render("apple").contains("An apple a day..."));
+/// assert!(render("orange").contains("Orange you glad?"));
+/// assert!(render("kiwi").contains("Some other fruit."));
+/// ```
+#[macro_export]
+macro_rules! match_view {
+  (@arms [$($arm:expr,)*]) => {
+    ::std::vec![$($arm,)*]
+  };
+  (@arms [$($arm:expr,)*] _ => $view:expr $(,)?) => {
+    $crate::match_view!(@arms [
+      $($arm,)*
+      ::leptos::IntoView::into_view($crate::match_::MatchFallback {
+        children: ::std::boxed::Box::new(move || {
+          ::leptos::Fragment::new(::std::vec![
+            ::leptos::IntoView::into_view($view),
+          ])
+        }),
+      }),
+    ])
+  };
+  (@arms [$($arm:expr,)*] $value:expr => $view:expr $(, $($rest:tt)*)?) => {
+    $crate::match_view!(@arms [
+      $($arm,)*
+      ::leptos::IntoView::into_view($crate::match_::MatchArm {
+        values: ::std::vec![$value],
+        weight: 1,
+        children: ::std::boxed::Box::new(move || {
+          ::leptos::Fragment::new(::std::vec![
+            ::leptos::IntoView::into_view($view),
+          ])
+        }),
+      }),
+    ] $($($rest)*)?)
+  };
+  ($signal:expr => { $($arms:tt)* }) => {
+    $crate::match_::Match(
+      $crate::match_::MatchProps::builder()
+        .signal($signal)
+        .children(::std::boxed::Box::new(move || {
+          ::leptos::Fragment::new($crate::match_view!(@arms [] $($arms)*))
+        }))
+        .build(),
+    )
+  };
+}
+
+/// Maps each variant of an enum to a fieldless key, so that [`Arm`]s can refer
+/// to a variant without having to build a full value of it.
+///
+/// This is usually derived with [`MatchKey`](derive@MatchKey), which
+/// generates a `{Enum}Key` enum with one fieldless variant per variant of
+/// `{Enum}`.
+pub trait MatchKey {
+  /// The key type, with one value per variant.
+  type Key: Copy + PartialEq + 'static;
+
+  /// Every key, in declaration order.
+  const KEYS: &'static [Self::Key];
+
+  /// Returns the key of this value's variant.
+  fn key(&self) -> Self::Key;
+}
+
+/// A value which can be matched by [`Match`] or [`use_match`].
+///
+/// This is implemented by:
+///
+/// - Signals, whose value is matched.
+/// - Anything callable with no arguments, such as a closure, whose return
+///   value is matched. The match is reactive over any signal read while
+///   calling it.
+/// - Constants of primitive types, such as [`bool`], integers, [`char`],
+///   `&'static str` and [`String`].
+///
+/// The type of the value is given by the type of the input, so that it does
+/// not have to be spelled out, which `Into<MaybeSignal<T>>` would require.
+/// Constants of other types can be matched by returning them from a closure.
+pub trait IntoMatchValue {
+  /// The type of the value matched.
+  type Value: 'static;
+
+  /// Turns this into a signal of the value matched.
+  fn into_signal(self) -> Signal<Self::Value>;
+}
+
+macro_rules! impl_into_match_value_for_signals {
+  ($($signal:ident),*) => {
+    $(
+      // With leptos' `nightly` feature, signals are callable, and therefore
+      // already covered by the implementation for closures
+      #[cfg(not(feature = "nightly"))]
+      impl<T> IntoMatchValue for $signal<T>
+      where
+        T: 'static,
+      {
+        type Value = T;
+
+        fn into_signal(self) -> Signal<T> {
+          self.into()
+        }
+      }
+    )*
+  };
+}
+
+impl_into_match_value_for_signals!(ReadSignal, RwSignal, Memo, Signal);
+
+#[cfg(not(feature = "nightly"))]
+impl<T> IntoMatchValue for MaybeSignal<T>
+where
+  T: Clone + 'static,
+{
+  type Value = T;
+
+  fn into_signal(self) -> Signal<T> {
+    match self {
+      MaybeSignal::Static(value) => Signal::derive(move || value.clone()),
+      MaybeSignal::Dynamic(signal) => signal,
+    }
+  }
+}
+
+macro_rules! impl_into_match_value_for_constants {
+  ($($ty:ty),*) => {
+    $(
+      impl IntoMatchValue for $ty {
+        type Value = $ty;
+
+        fn into_signal(self) -> Signal<$ty> {
+          Signal::derive(move || self.clone())
+        }
+      }
+    )*
+  };
+}
+
+impl_into_match_value_for_constants!(bool, char, &'static str, String);
+impl_into_match_value_for_constants!(i8, i16, i32, i64, i128, isize);
+impl_into_match_value_for_constants!(u8, u16, u32, u64, u128, usize);
+
+impl<F, T> IntoMatchValue for F
+where
+  F: Fn() -> T + 'static,
+  T: 'static,
+{
+  type Value = T;
+
+  fn into_signal(self) -> Signal<T> {
+    Signal::derive(self)
+  }
+}
+
+#[cfg(debug_assertions)]
+fn run_debug_checks<T: 'static>(arms: &[Transparent]) {
+  let is_arm = |arm: &&Transparent| {
+    arm.downcast_ref::<MatchArm<T>>().is_some()
+      || arm.downcast_ref::<MatchBindingArm<T>>().is_some()
+      || arm.downcast_ref::<MatchRangeArm<T>>().is_some()
+  };
+  let is_fallback =
+    |arm: &&Transparent| arm.downcast_ref::<MatchFallback>().is_some();
+
+  let arms = arms.iter().filter(|arm| is_arm(arm) || is_fallback(arm));
+
+  // Make sure <Fallback /> is last
+  if let Some(pos) = arms.clone().position(|arm| is_fallback(&arm)) {
+    assert_eq!(
+      pos,
+      arms.clone().count() - 1,
+      "`<Fallback />` must be the last child of `<Match />`"
+    );
+  }
+
+  // Make sure there is no more than 1 <Fallback />
+  assert!(
+    arms.filter(is_fallback).count() <= 1,
+    "there must not be more than 1 `<Fallback />` children within `<Match />`"
+  );
+}
@@ -0,0 +1,121 @@
+//! Optional rendering for [`leptos`].
+//!
+//! Please refer to [`maybe`] for usage examples.
+
+use leptos::*;
+
+/// Renders a view from the value inside an optional signal, with an optional
+/// fallback for when it is [`None`].
+///
+/// This is the Rust-level counterpart to declaring each branch as a child
+/// component, for when building the view programmatically reads better.
+///
+/// # Examples
+///
+/// ### With a fallback
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (user, _) = create_signal(Some("Alice"));
+///
+/// view! {
+///   <div>
+///     {maybe(user)
+///       .map(move |user| view! { <p>"Hello, " {user} "!"</p> })
+///       .or(move || view! { <p>"Please log in."</p> })}
+///   </div>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Without a fallback
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (error, _) = create_signal(None::<String>);
+///
+/// view! {
+///   <div>
+///     {maybe(error).map(move |error| view! { <p>{error}</p> })}
+///   </div>
+/// };
+/// # runtime.dispose();
+/// ```
+pub fn maybe<T>(signal: impl Into<MaybeSignal<Option<T>>>) -> Maybe<T>
+where
+  T: Clone + 'static,
+{
+  Maybe {
+    signal: signal.into(),
+  }
+}
+
+/// An optional signal waiting to be given a view for its [`Some`] value.
+///
+/// Created by [`maybe`].
+pub struct Maybe<T: 'static> {
+  signal: MaybeSignal<Option<T>>,
+}
+
+impl<T> Maybe<T>
+where
+  T: Clone + 'static,
+{
+  /// Sets the view to render whenever the signal is [`Some`].
+  pub fn map<F, V>(self, f: F) -> MaybeMap<T, F>
+  where
+    F: Fn(T) -> V + 'static,
+    V: IntoView,
+  {
+    MaybeMap {
+      signal: self.signal,
+      f,
+    }
+  }
+}
+
+/// An optional signal with a view for its [`Some`] value. Renders nothing when
+/// the signal is [`None`], unless a fallback is provided with
+/// [`MaybeMap::or`].
+///
+/// Created by [`Maybe::map`].
+pub struct MaybeMap<T: 'static, F> {
+  signal: MaybeSignal<Option<T>>,
+  f: F,
+}
+
+impl<T, F, V> MaybeMap<T, F>
+where
+  T: Clone + 'static,
+  F: Fn(T) -> V + 'static,
+  V: IntoView,
+{
+  /// Sets the view to render whenever the signal is [`None`].
+  pub fn or<G, W>(self, fallback: G) -> impl IntoView
+  where
+    G: Fn() -> W + 'static,
+    W: IntoView,
+  {
+    let Self { signal, f } = self;
+
+    move || match signal.get() {
+      Some(value) => f(value).into_view(),
+      None => fallback().into_view(),
+    }
+  }
+}
+
+impl<T, F, V> IntoView for MaybeMap<T, F>
+where
+  T: Clone + 'static,
+  F: Fn(T) -> V + 'static,
+  V: IntoView,
+{
+  fn into_view(self) -> View {
+    self.or(|| ()).into_view()
+  }
+}
@@ -0,0 +1,148 @@
+//! Rendering metrics of [`If`](crate::if_::If) and
+//! [`Match`](crate::match_::Match), for finding the conditionals which
+//! re-render the most.
+//!
+//! Metrics are only recorded with the `metrics` feature, and are kept per
+//! thread, grouped by component name and `debug_name`.
+//!
+//! # Examples
+//! ```rust
+//! use leptos::*;
+//! use leptos_declarative::{
+//!   metrics,
+//!   prelude::*,
+//! };
+//!
+//! leptos::ssr::render_to_string(|| {
+//!   view! {
+//!   <If signal=true debug_name="sidebar">
+//!     <Then>"Sidebar"</Then>
+//!   </If>
+//!   }
+//! });
+//!
+//! let sidebar = metrics::snapshot()
+//!   .into_iter()
+//!   .find(|(name, _)| name.contains("sidebar"))
+//!   .map(|(_, metrics)| metrics)
+//!   .unwrap();
+//!
+//! assert_eq!(sidebar.renders, 1);
+//!
+//! // `dump` formats the same metrics as a table, e.g. for logging
+//! assert!(metrics::dump().contains("sidebar"));
+//! ```
+//!
+//! Conditions changing together in a [`batch`](leptos::batch) are evaluated
+//! once:
+//!
+//! ```rust
+//! use leptos::*;
+//! use leptos_declarative::{
+//!   metrics,
+//!   prelude::*,
+//! };
+//!
+//! # let runtime = create_runtime();
+//! let (a, set_a) = create_signal(false);
+//! let (b, set_b) = create_signal(false);
+//!
+//! let evaluations = || {
+//!   metrics::snapshot()
+//!     .into_iter()
+//!     .find(|(name, _)| name.contains("batched"))
+//!     .map(|(_, metrics)| metrics.evaluations)
+//!     .unwrap()
+//! };
+//!
+//! // `keep_alive` selects branches in an effect, which also runs on the
+//! // server
+//! let _view = view! {
+//! <If signal=a keep_alive=true debug_name="batched">
+//!   <Then>"A"</Then>
+//!   <ElseIf signal=b>"B"</ElseIf>
+//!   <Else>"Neither"</Else>
+//! </If>
+//! };
+//!
+//! let before = evaluations();
+//!
+//! batch(|| {
+//!   set_a.set(true);
+//!   set_b.set(true);
+//! });
+//!
+//! assert_eq!(evaluations(), before + 1);
+//! # runtime.dispose();
+//! ```
+
+use std::{
+  cell::RefCell,
+  cmp::Reverse,
+  collections::BTreeMap,
+  fmt::Write,
+};
+
+/// The metrics of all the conditionals sharing a name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConditionalMetrics {
+  /// How many times a branch was selected, i.e. how many times the
+  /// conditions were evaluated as a whole.
+  pub evaluations: u64,
+  /// How many times a branch was built.
+  pub renders: u64,
+}
+
+thread_local! {
+  static METRICS: RefCell<BTreeMap<String, ConditionalMetrics>> =
+    const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Returns the metrics recorded so far, by component name, sorted by name.
+pub fn snapshot() -> Vec<(String, ConditionalMetrics)> {
+  METRICS.with(|metrics| {
+    metrics
+      .borrow()
+      .iter()
+      .map(|(name, metrics)| (name.clone(), *metrics))
+      .collect()
+  })
+}
+
+/// Formats the metrics recorded so far as a table, one line per component
+/// name, with the components rendering the most first.
+pub fn dump() -> String {
+  let mut metrics = snapshot();
+
+  metrics.sort_by_key(|(_, metrics)| Reverse(metrics.renders));
+
+  let mut dump = String::from("renders\tevaluations\tcomponent\n");
+
+  for (name, metrics) in metrics {
+    let _ =
+      writeln!(dump, "{}\t{}\t{name}", metrics.renders, metrics.evaluations);
+  }
+
+  dump
+}
+
+/// Forgets all the metrics recorded so far.
+pub fn reset() {
+  METRICS.with(|metrics| metrics.borrow_mut().clear());
+}
+
+pub(crate) fn record(name: &str, record: impl FnOnce(&mut ConditionalMetrics)) {
+  METRICS.with(|metrics| {
+    let mut metrics = metrics.borrow_mut();
+
+    if let Some(metrics) = metrics.get_mut(name) {
+      record(metrics);
+    } else {
+      let mut new_metrics = ConditionalMetrics::default();
+
+      record(&mut new_metrics);
+
+      metrics.insert(name.to_string(), new_metrics);
+    }
+  });
+}
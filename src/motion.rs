@@ -0,0 +1,261 @@
+//! Motion preferences for [`leptos`].
+//!
+//! Any component in this crate which animates content in or out must consult
+//! [`use_motion_allowed`] and unmount immediately, without running its
+//! enter/leave animation, when motion is not allowed.
+//!
+//! Transitions which should run in sync share a [`TransitionTimeline`].
+
+use leptos::*;
+use std::time::Duration;
+use wasm_bindgen::{
+  closure::Closure,
+  JsCast,
+};
+
+const REDUCED_MOTION_QUERY: &str = "(prefers-reduced-motion: reduce)";
+
+/// Returns a signal which is `true` when the user has asked for reduced motion
+/// in their operating system or browser settings.
+///
+/// The signal reacts to the preference being changed at runtime. Outside the
+/// browser it is always `false`.
+pub fn use_prefers_reduced_motion() -> Signal<bool> {
+  let (reduced_motion, set_reduced_motion) = create_signal(false);
+
+  if !leptos_dom::is_browser() {
+    return reduced_motion.into();
+  }
+
+  let query = match window().match_media(REDUCED_MOTION_QUERY) {
+    Ok(Some(query)) => query,
+    _ => return reduced_motion.into(),
+  };
+
+  set_reduced_motion.set(query.matches());
+
+  let on_change = Closure::<dyn Fn(web_sys::MediaQueryListEvent)>::new(
+    move |e: web_sys::MediaQueryListEvent| set_reduced_motion.set(e.matches()),
+  );
+
+  let _ = query.add_event_listener_with_callback(
+    "change",
+    on_change.as_ref().unchecked_ref(),
+  );
+
+  on_cleanup(move || {
+    let _ = query.remove_event_listener_with_callback(
+      "change",
+      on_change.as_ref().unchecked_ref(),
+    );
+  });
+
+  reduced_motion.into()
+}
+
+/// Returns a signal which is `true` when enter/leave animations should run.
+///
+/// This is the case when the user has not asked for reduced motion, or when
+/// `force_motion` is `true`. Components which must animate regardless of the
+/// user's preference (e.g. because the animation conveys meaning) should
+/// expose a `force_motion` prop and pass it through here, as
+/// [`If`](crate::if_::If), [`PortalOutput`](crate::portal::PortalOutput) and
+/// [`TransitionTimeline`] do.
+pub fn use_motion_allowed(force_motion: MaybeSignal<bool>) -> Signal<bool> {
+  let reduced_motion = use_prefers_reduced_motion();
+
+  Signal::derive(move || force_motion.get() || !reduced_motion.get())
+}
+
+/// Returns how long the CSS transitions of `el` take, from the longest
+/// `transition-duration` and `transition-delay`, so that a leave animation
+/// can be cut short when no `transitionend` event arrives, e.g. because the
+/// leave class has no transition. This is zero outside the browser.
+pub(crate) fn transition_timeout(el: &web_sys::Element) -> Duration {
+  let Ok(Some(style)) = window().get_computed_style(el) else {
+    return Duration::ZERO;
+  };
+
+  let longest = |property: &str| {
+    style
+      .get_property_value(property)
+      .unwrap_or_default()
+      .split(',')
+      .filter_map(parse_css_time)
+      .max()
+      .unwrap_or_default()
+  };
+
+  longest("transition-duration") + longest("transition-delay")
+}
+
+/// Parses a CSS `<time>`, such as `0.2s` or `150ms`. Negative times, which
+/// only shorten transitions, count as zero.
+fn parse_css_time(time: &str) -> Option<Duration> {
+  let time = time.trim();
+
+  let seconds = match time.strip_suffix("ms") {
+    Some(millis) => millis.parse::<f64>().ok()? / 1000.0,
+    None => time.strip_suffix('s')?.parse::<f64>().ok()?,
+  };
+
+  Duration::try_from_secs_f64(seconds.max(0.0)).ok()
+}
+
+/// A phase of a [`TransitionTimeline`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransitionPhase {
+  /// No transition is running.
+  #[default]
+  Idle,
+  /// The old content is leaving.
+  Leave,
+  /// The new content is entering.
+  Enter,
+}
+
+/// A timeline shared by several transitions, so that they leave and enter in
+/// sync rather than each on its own schedule, e.g. for a page transition
+/// involving several [`If`](crate::if_::If)s.
+///
+/// Provide it as context, and every [`If`](crate::if_::If) with a
+/// `transition` below it follows it: when the branch of any of them changes,
+/// the timeline is started, and each one whose branch changed keeps its old
+/// branch through the [`Leave`](TransitionPhase::Leave) phase, then swaps to
+/// its new branch for the [`Enter`](TransitionPhase::Enter) phase. An
+/// [`If`](crate::if_::If) without a timeline in context uses one of its own.
+///
+/// Custom components can follow the same timeline by reading
+/// [`phase`](Self::phase).
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::time::Duration;
+///
+/// # let runtime = create_runtime();
+/// let (logged_in, _) = create_signal(false);
+///
+/// provide_context(TransitionTimeline::new(
+///   Duration::from_millis(150),
+///   Duration::from_millis(250),
+///   false.into(),
+/// ));
+///
+/// view! {
+///   <header>
+///     <If signal=logged_in transition="fade">
+///       <Then>"Welcome back!"</Then>
+///       <Else>"Please log in."</Else>
+///     </If>
+///   </header>
+///   <main>
+///     <If signal=logged_in transition="slide">
+///       <Then>"Your dashboard"</Then>
+///       <Else>"Log in form"</Else>
+///     </If>
+///   </main>
+/// };
+/// # runtime.dispose();
+/// ```
+#[derive(Clone, Copy)]
+pub struct TransitionTimeline {
+  phase: RwSignal<TransitionPhase>,
+  /// Incremented on every start, so that the timers of an interrupted run
+  /// do nothing.
+  run: StoredValue<u64>,
+  leave: Duration,
+  enter: Duration,
+  motion_allowed: Signal<bool>,
+}
+
+impl Default for TransitionTimeline {
+  /// A timeline with 200ms leave and enter phases.
+  fn default() -> Self {
+    Self::new(
+      Duration::from_millis(200),
+      Duration::from_millis(200),
+      false.into(),
+    )
+  }
+}
+
+impl TransitionTimeline {
+  /// Creates a timeline with the given durations for the leave and enter
+  /// phases, which should match those of the CSS transitions following it.
+  ///
+  /// With `force_motion`, the timeline runs even when the user has asked for
+  /// reduced motion, please refer to [`use_motion_allowed`].
+  pub fn new(
+    leave: Duration,
+    enter: Duration,
+    force_motion: MaybeSignal<bool>,
+  ) -> Self {
+    Self {
+      phase: create_rw_signal(TransitionPhase::Idle),
+      run: store_value(0),
+      leave,
+      enter,
+      motion_allowed: use_motion_allowed(force_motion),
+    }
+  }
+
+  /// Returns a signal to the current phase.
+  pub fn phase(&self) -> Signal<TransitionPhase> {
+    self.phase.read_only().into()
+  }
+
+  /// Starts a transition, unless one is already in its leave phase, in which
+  /// case the caller joins it.
+  ///
+  /// Returns whether a transition is running. Outside the browser, or when
+  /// motion is not allowed, nothing runs and `false` is returned, in which
+  /// case the caller should swap its content immediately.
+  pub fn start(&self) -> bool {
+    self.start_with(false)
+  }
+
+  /// Like [`start`](Self::start), but runs even when motion is not allowed
+  /// if `force_motion` is true, for a follower which forces motion itself.
+  pub(crate) fn start_with(&self, force_motion: bool) -> bool {
+    let motion_allowed = force_motion || self.motion_allowed.get_untracked();
+
+    if !leptos_dom::is_browser() || !motion_allowed {
+      return false;
+    }
+
+    if self.phase.get_untracked() == TransitionPhase::Leave {
+      return true;
+    }
+
+    self.run.update_value(|run| *run += 1);
+
+    let run = self.run.get_value();
+    let this = *self;
+
+    self.phase.set(TransitionPhase::Leave);
+
+    set_timeout(
+      move || {
+        if this.run.try_get_value() != Some(run) {
+          return;
+        }
+
+        this.phase.try_set(TransitionPhase::Enter);
+
+        set_timeout(
+          move || {
+            if this.run.try_get_value() == Some(run) {
+              this.phase.try_set(TransitionPhase::Idle);
+            }
+          },
+          this.enter,
+        );
+      },
+      self.leave,
+    );
+
+    true
+  }
+}
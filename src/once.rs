@@ -0,0 +1,107 @@
+//! Latching conditionals for [`leptos`].
+//!
+//! Please refer to [`RenderOnce`] for usage examples.
+
+use crate::condition::IntoCondition;
+use leptos::*;
+use std::cell::Cell;
+
+/// Renders its children the first time a condition is true, and keeps them
+/// mounted from then on, whatever the condition.
+///
+/// This suits content which is deferred but permanent, such as a heavy tab
+/// panel which should only be built once the tab is first opened, and should
+/// then keep its state when switching tabs. It sits between the other ways
+/// of rendering conditionally:
+///
+/// - [`If`](crate::if_::If) unmounts its branch whenever the condition is
+///   false again.
+/// - `keep_alive` on [`If`](crate::if_::If) also builds a branch the first
+///   time it is shown and keeps it mounted, but switches between branches,
+///   hiding inactive ones with `display: none`. Only `preload` and
+///   `measure_all` build branches up front.
+/// - [`RenderOnce`] has a single branch and renders nothing, or `fallback`,
+///   until the condition is first true, and never unmounts its children
+///   after that.
+///
+/// The condition accepts anything implementing [`IntoCondition`], like
+/// [`If`](crate::if_::If). It stops being tracked once it has been true, so
+/// the children are rendered exactly once, and `fallback` is never shown
+/// again.
+///
+/// Hiding the children while the condition is false, e.g. with a `hidden`
+/// attribute, is left to the children.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[component]
+/// fn Reports() -> impl IntoView {
+///   view! { <table>"..."</table> }
+/// }
+///
+/// # let runtime = create_runtime();
+/// let (tab, set_tab) = create_signal("overview");
+///
+/// view! {
+///   <div hidden=move || tab.get() != "reports">
+///     <RenderOnce when=move || tab.get() == "reports">
+///       // Built the first time the tab is opened, then kept
+///       <Reports />
+///     </RenderOnce>
+///   </div>
+/// };
+///
+/// set_tab.set("reports");
+/// set_tab.set("overview");
+/// # runtime.dispose();
+/// ```
+///
+/// Until the condition is first true, `fallback` is shown:
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <RenderOnce when=|| false fallback=|| "Not opened yet">
+///     "Reports"
+///   </RenderOnce>
+///   }
+/// });
+///
+/// assert!(html.contains("Not opened yet"));
+/// assert!(!html.contains("Reports"));
+/// ```
+#[component]
+pub fn RenderOnce<C>(
+  /// The condition which, once true, renders the children.
+  when: C,
+  /// What to show until the condition is first true. Nothing is shown by
+  /// default.
+  #[prop(optional, into)]
+  fallback: Option<ViewFn>,
+  /// What to render once the condition has been true.
+  children: Children,
+) -> impl IntoView
+where
+  C: IntoCondition + 'static,
+{
+  // Once latched, the memo no longer reads the condition, so it never
+  // changes again
+  let latched = create_memo(move |latched: Option<&bool>| {
+    latched.copied().unwrap_or_default() || when.evaluate()
+  });
+
+  let children = Cell::new(Some(children));
+
+  move || {
+    if !latched.get() {
+      return fallback.as_ref().map(ViewFn::run).into_view();
+    }
+
+    children.take().map(|children| children()).into_view()
+  }
+}
@@ -5,212 +5,3141 @@
 //!
 //! For usage examples, please refer to [`PortalInput`].
 
-use leptos::*;
-use std::any::{
-  Any,
-  TypeId,
+use crate::{
+  anchor::Rect,
+  motion::{
+    transition_timeout,
+    use_motion_allowed,
+  },
+  util::{
+    self,
+    component_name,
+    observe_size,
+  },
 };
+use leptos::{
+  html::ElementDescriptor,
+  *,
+};
+use std::{
+  any::{
+    Any,
+    TypeId,
+  },
+  cell::{
+    Cell,
+    RefCell,
+  },
+  fmt,
+  future::Future,
+  rc::Rc,
+  time::Duration,
+};
+use wasm_bindgen::{
+  closure::Closure,
+  JsCast,
+};
+
+api_planning! {
+  struct PortalA;
+  struct PortalB;
+
+  // Somewhere up there
+  view! {
+    <PortalProvider>
+      // rest of your app
+    </PortalProvider>
+  }
+
+  // Where you want your portal to output
+  view! {
+    <h1>"Where'd these come from???"</h1>
+    <PortalOutput id=PortalA />
+    <PortalOutput id=PortalB />
+  }
+
+  // Where you want portal contents to go
+  view! {
+    <PortalInput id=PortalA>
+      <p>"I used a portal to get here..."</p>
+    </PortalInput>
+    <PortalInput id=PortalB>
+      <p>"The cake was really nice"</p>
+      </PortalInput>
+  }
+}
+
+const GLOBAL_PORTAL_ROOT_ID: &str = "leptos-declarative-global-portal";
+
+/// Errors which can occur when using portals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortalError {
+  /// No [`PortalProvider`] was found above the component using portals.
+  NoProvider,
+}
+
+impl fmt::Display for PortalError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::NoProvider => f.write_str(
+        "failed to find `PortalCtx`, make sure you are using \
+         `<PortalProvider />` somewhere near the root of the app",
+      ),
+    }
+  }
+}
+
+impl std::error::Error for PortalError {}
+
+/// The portal registry shared by [`PortalInput`] and [`PortalOutput`].
+///
+/// This is provided by [`PortalProvider`] and can be obtained with
+/// [`use_portal_ctx`]. It is exposed so that third-party crates can build
+/// their own portal helpers on top of the same registry the components in this
+/// module use.
+///
+/// Portals are identified by the [`TypeId`] of the `id` type passed to
+/// [`PortalInput`] and [`PortalOutput`], e.g. `TypeId::of::<PortalId>()`.
+///
+/// There is no separate step creating a portal: its content signal is created
+/// the first time either side needs it, by [`get`](Self::get) for outputs or
+/// by [`register`](Self::register) for inputs, and is then shared by both.
+/// Whether an output or an input of a portal is rendered first therefore
+/// makes no difference to what the portal holds.
+///
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::any::TypeId;
+///
+/// struct Toast;
+///
+/// fn registered(output_first: bool) -> bool {
+///   let runtime = create_runtime();
+///
+///   let content = create_rw_signal(None);
+///
+///   let output = || view! { <PortalOutput id=Toast /> }.into_view();
+///   let input = || view! { <PortalInput id=Toast>"Saved!"</PortalInput> };
+///
+///   view! {
+///     <PortalProvider>
+///       {if output_first {
+///         vec![output(), input().into_view()]
+///       } else {
+///         vec![input().into_view(), output()]
+///       }}
+///       {content.set(Some(use_portal_ctx().unwrap().get(TypeId::of::<Toast>())))}
+///     </PortalProvider>
+///   };
+///
+///   let registered =
+///     content.get_untracked().unwrap().with_untracked(Option::is_some);
+///
+///   runtime.dispose();
+///
+///   registered
+/// }
+///
+/// assert!(registered(true));
+/// assert!(registered(false));
+/// ```
+#[derive(Clone, Copy)]
+pub struct PortalCtx {
+  portals: StoredValue<Vec<(TypeId, RwSignal<Option<ChildrenFn>>)>>,
+  /// The number of [`PortalInput`]s currently mounted for each portal.
+  inputs: StoredValue<Vec<(TypeId, RwSignal<usize>)>>,
+  /// The number of outputs currently mounted for each portal.
+  output_counts: StoredValue<Vec<(TypeId, RwSignal<usize>)>>,
+  /// The ids of the portals whose content is currently being rendered, used
+  /// to detect a portal being rendered within its own content.
+  rendering: StoredValue<Vec<TypeId>>,
+  /// The dedup key of the content registered for each portal, if any.
+  dedup_keys: StoredValue<Vec<(TypeId, String)>>,
+  /// The aliases of the provider, please refer to [`PortalAlias`].
+  aliases: StoredValue<Vec<PortalAlias>>,
+  /// The crossfade of outputs which do not set one.
+  crossfade: Option<&'static str>,
+  /// Whether outputs render content, please refer to [`PortalProvider`].
+  enabled: Signal<bool>,
+  /// Whether content is kept in the persisted registry, please refer to
+  /// [`PortalProvider`].
+  persist: bool,
+  /// The priority offset of the provider, already combined with those of
+  /// the providers above it.
+  priority_offset: i32,
+  /// Whether outputs sharing an id are treated as a bug, please refer to
+  /// [`PortalProvider`].
+  #[cfg(debug_assertions)]
+  strict: bool,
+  /// The ids of the mounted outputs, and whether each of them broadcasts.
+  #[cfg(debug_assertions)]
+  outputs: StoredValue<Vec<(TypeId, bool)>>,
+  /// The ids used by inputs and outputs, and the names of their types, for
+  /// [`PortalDebugOverlay`].
+  #[cfg(feature = "devtools")]
+  named_ids: RwSignal<Vec<(TypeId, &'static str)>>,
+  /// The owner of the context, which owns the signals of every portal rather
+  /// than whichever component happened to use a portal first.
+  owner: Option<Owner>,
+}
+
+impl PortalCtx {
+  fn new(
+    strict: bool,
+    aliases: Vec<PortalAlias>,
+    crossfade: Option<&'static str>,
+    enabled: Signal<bool>,
+    persist: bool,
+    priority_offset: i32,
+  ) -> Self {
+    #[cfg(not(debug_assertions))]
+    let _ = strict;
+
+    Self {
+      portals: store_value(Default::default()),
+      inputs: store_value(Default::default()),
+      output_counts: store_value(Default::default()),
+      rendering: store_value(Default::default()),
+      dedup_keys: store_value(Default::default()),
+      aliases: store_value(aliases),
+      crossfade,
+      enabled,
+      // The registry outlives requests on the server, so it would leak
+      // content between them
+      persist: persist && leptos_dom::is_browser(),
+      priority_offset,
+      #[cfg(debug_assertions)]
+      strict,
+      #[cfg(debug_assertions)]
+      outputs: store_value(Default::default()),
+      #[cfg(feature = "devtools")]
+      named_ids: create_rw_signal(Vec::new()),
+      owner: Owner::current(),
+    }
+  }
+
+  /// Sets the children rendered by any [`PortalOutput`] with the matching
+  /// `id`, replacing whatever content was previously registered.
+  pub fn register(&self, id: TypeId, children: ChildrenFn) {
+    self.set_dedup_key(id, None);
+    self.set_content(id, Some(children));
+  }
+
+  /// Like [`register`](Self::register), but does nothing if the children
+  /// currently registered for `id` were registered with the same `key`, so
+  /// that the same content is not registered twice.
+  ///
+  /// Returns whether `children` were registered.
+  pub fn register_dedup(
+    &self,
+    id: TypeId,
+    key: String,
+    children: ChildrenFn,
+  ) -> bool {
+    let id = self.resolve(id);
+    let slot = self.slot(id);
+
+    let is_duplicate = slot.with_untracked(Option::is_some)
+      && self.dedup_keys.with_value(|keys| {
+        keys
+          .iter()
+          .any(|(type_id, other)| *type_id == id && *other == key)
+      });
+
+    if is_duplicate {
+      return false;
+    }
+
+    self.set_dedup_key(id, Some(key));
+    self.set_content(id, Some(children));
+
+    true
+  }
+
+  /// Like [`register`](Self::register), but does nothing if children are
+  /// already registered for `id`.
+  ///
+  /// Returns whether `children` were registered.
+  pub fn register_once(&self, id: TypeId, children: ChildrenFn) -> bool {
+    let slot = self.slot(id);
+
+    if slot.with_untracked(Option::is_some) {
+      return false;
+    }
+
+    self.set_dedup_key(id, None);
+    self.set_content(id, Some(children));
+
+    true
+  }
+
+  /// Returns a signal to the children currently registered for `id`, if any.
+  pub fn get(&self, id: TypeId) -> Signal<Option<ChildrenFn>> {
+    self.slot(id).read_only().into()
+  }
+
+  /// Removes the children registered for `id`, causing any matching
+  /// [`PortalOutput`] to render nothing.
+  ///
+  /// Under a [`PortalProvider`] which persists, this also removes them from
+  /// the persisted registry.
+  pub fn clear(&self, id: TypeId) {
+    if self.persist {
+      persist_content(self.resolve(id), None);
+    }
+
+    self.clear_mounted(id);
+  }
+
+  /// Like [`clear`](Self::clear), but keeps the children in the persisted
+  /// registry, for inputs unmounting, which they survive.
+  fn clear_mounted(&self, id: TypeId) {
+    let id = self.resolve(id);
+
+    self.set_dedup_key(id, None);
+
+    let slot = self.portals.with_value(|portals| {
+      portals
+        .iter()
+        .find(|(type_id, _)| *type_id == id)
+        .map(|(_, children)| *children)
+    });
+
+    if let Some(slot) = slot {
+      slot.set(None);
+    }
+  }
+
+  /// Returns a signal to whether outputs render content, which is false while
+  /// the [`PortalProvider`] is disabled.
+  pub fn is_enabled(&self) -> Signal<bool> {
+    self.enabled
+  }
+
+  /// Returns the priority offset of the [`PortalProvider`], i.e. the sum of
+  /// its own `priority_offset` and of those of the providers above it, to be
+  /// added to the priority of every input below it.
+  pub fn priority_offset(&self) -> i32 {
+    self.priority_offset
+  }
+
+  /// Returns `content`, but with no content while the provider is disabled,
+  /// for outputs to render.
+  fn while_enabled(
+    &self,
+    content: Signal<Option<ChildrenFn>>,
+  ) -> Signal<Option<ChildrenFn>> {
+    let enabled = self.enabled;
+
+    Signal::derive(move || if enabled.get() { content.get() } else { None })
+  }
+
+  /// Returns a signal to the number of [`PortalInput`]s currently mounted for
+  /// `id`.
+  ///
+  /// Every mounted input is counted, even though only one of them is
+  /// rendered at a time.
+  pub fn input_count(&self, id: TypeId) -> Signal<usize> {
+    self.input_count_slot(id).read_only().into()
+  }
+
+  /// Returns the [`TypeId`] of the portal id `id`, remembering the name of
+  /// its type for [`PortalDebugOverlay`].
+  fn id_of<T: Any>(&self, id: &T) -> TypeId {
+    let _ = id;
+
+    let type_id = TypeId::of::<T>();
+
+    #[cfg(feature = "devtools")]
+    if !self
+      .named_ids
+      .with_untracked(|ids| ids.iter().any(|(other, _)| *other == type_id))
+    {
+      self
+        .named_ids
+        .update(|ids| ids.push((type_id, std::any::type_name::<T>())));
+    }
+
+    type_id
+  }
+
+  /// Counts an input for `id` until the current owner is cleaned up, i.e.
+  /// until the component calling this unmounts.
+  fn count_input(&self, id: TypeId) {
+    count_until_cleanup(self.input_count_slot(id));
+  }
+
+  /// Returns a signal to the number of outputs currently mounted for `id`.
+  pub fn output_count(&self, id: TypeId) -> Signal<usize> {
+    self.output_count_slot(id).read_only().into()
+  }
+
+  /// Counts an output for `id` until the current owner is cleaned up.
+  fn count_output(&self, id: TypeId) {
+    count_until_cleanup(self.output_count_slot(id));
+  }
+
+  /// Removes the children registered for `id` if they are `children`, i.e.
+  /// unless another input has replaced them since.
+  fn clear_if_registered(&self, id: TypeId, children: &ChildrenFn) {
+    let is_registered = self.slot(id).with_untracked(|current| {
+      current
+        .as_ref()
+        .is_some_and(|current| Rc::ptr_eq(current, children))
+    });
+
+    if is_registered {
+      self.clear_mounted(id);
+    }
+  }
+
+  /// Makes the outputs of `id` render its content again if it is `children`,
+  /// even though it has not changed.
+  fn refresh_if_registered(&self, id: TypeId, children: &ChildrenFn) {
+    let slot = self.slot(id);
+
+    let is_registered = slot.with_untracked(|current| {
+      current
+        .as_ref()
+        .is_some_and(|current| Rc::ptr_eq(current, children))
+    });
+
+    if is_registered {
+      slot.update(|_| ());
+    }
+  }
+
+  /// Tracks an output of `id` until the current owner is cleaned up.
+  ///
+  /// In strict mode, panics if another output of `id` is mounted, unless both
+  /// of them broadcast.
+  #[cfg(debug_assertions)]
+  fn add_output(&self, id: TypeId, broadcast: bool, name: &str) {
+    let id = self.resolve(id);
+
+    let collides = self.outputs.with_value(|outputs| {
+      outputs
+        .iter()
+        .any(|(type_id, other)| *type_id == id && !(broadcast && *other))
+    });
+
+    if self.strict && collides {
+      panic!(
+        "{name} shares its `id` with another output under a \
+         strict `<PortalProvider />`; if the content is meant to be shown \
+         in several places, set `broadcast` on every output with this `id`"
+      );
+    }
+
+    self
+      .outputs
+      .update_value(|outputs| outputs.push((id, broadcast)));
+
+    let outputs = self.outputs;
+
+    on_cleanup(move || {
+      outputs.try_update_value(|outputs| {
+        if let Some(i) =
+          outputs.iter().position(|output| *output == (id, broadcast))
+        {
+          outputs.remove(i);
+        }
+      });
+    });
+  }
+
+  /// Sets the content of `id`, keeping it in the persisted registry as well
+  /// if this context persists.
+  fn set_content(&self, id: TypeId, children: Option<ChildrenFn>) {
+    let id = self.resolve(id);
+
+    if self.persist {
+      persist_content(id, children.clone());
+    }
+
+    self.slot(id).set(children);
+  }
+
+  /// Sets the dedup key of the content registered for `id`.
+  fn set_dedup_key(&self, id: TypeId, key: Option<String>) {
+    let id = self.resolve(id);
+
+    self.dedup_keys.update_value(|keys| {
+      keys.retain(|(type_id, _)| *type_id != id);
+
+      if let Some(key) = key {
+        keys.push((id, key));
+      }
+    });
+  }
+
+  /// Returns the id `id` is an alias of, or `id` itself if it is not an
+  /// alias.
+  fn resolve(&self, id: TypeId) -> TypeId {
+    self.aliases.with_value(|aliases| {
+      aliases
+        .iter()
+        .find(|alias| alias.from == id)
+        .map_or(id, |alias| alias.to)
+    })
+  }
+
+  /// Whether the owner of this context has not been disposed of yet.
+  fn is_alive(&self) -> bool {
+    self.portals.try_with_value(|_| ()).is_some()
+  }
+
+  /// Gets the content signal for `id`, creating it if this is the first time
+  /// `id` is seen.
+  fn slot(&self, id: TypeId) -> RwSignal<Option<ChildrenFn>> {
+    let id = self.resolve(id);
+
+    let persist = self.persist;
+
+    find_or_create(self.owner, self.portals, id, || {
+      if persist {
+        persisted_content(id)
+      } else {
+        None
+      }
+    })
+  }
+
+  /// Gets the input count signal for `id`, creating it if this is the first
+  /// time `id` is seen.
+  fn input_count_slot(&self, id: TypeId) -> RwSignal<usize> {
+    let id = self.resolve(id);
+
+    find_or_create(self.owner, self.inputs, id, || 0)
+  }
+
+  /// Gets the output count signal for `id`, creating it if this is the first
+  /// time `id` is seen.
+  fn output_count_slot(&self, id: TypeId) -> RwSignal<usize> {
+    let id = self.resolve(id);
+
+    find_or_create(self.owner, self.output_counts, id, || 0)
+  }
+
+  /// Renders the content of the portal `id`.
+  ///
+  /// If `id` is already being rendered further up the tree, i.e. a
+  /// [`PortalOutput`] ended up inside the content of the very portal it
+  /// outputs, nothing is rendered, as this would otherwise recurse forever.
+  fn render(
+    &self,
+    id: TypeId,
+    children: &ChildrenFn,
+    debug_name: Option<&str>,
+  ) -> View {
+    let id = self.resolve(id);
+
+    if self
+      .rendering
+      .with_value(|rendering| rendering.contains(&id))
+    {
+      #[cfg(debug_assertions)]
+      logging::error!(
+        "{} was rendered inside the content of its own portal, which would \
+         recurse forever, so nothing was rendered instead; make sure \
+         `<PortalInput />` does not contain the `<PortalOutput />` with the \
+         same `id`, directly or indirectly",
+        component_name("PortalOutput", debug_name),
+      );
+      #[cfg(not(debug_assertions))]
+      let _ = debug_name;
+
+      return ().into_view();
+    }
+
+    self.rendering.update_value(|rendering| rendering.push(id));
+
+    let view = children().into_view();
+
+    self.rendering.update_value(|rendering| {
+      rendering.pop();
+    });
+
+    view
+  }
+}
+
+/// Increments `count`, and decrements it again when the current owner is
+/// cleaned up.
+fn count_until_cleanup(count: RwSignal<usize>) {
+  count.update(|count| *count += 1);
+
+  on_cleanup(move || {
+    count.try_update(|count| *count = count.saturating_sub(1));
+  });
+}
+
+/// Gets the signal stored for `id` in `signals`, creating it under `owner`
+/// with `init` if there is none yet.
+fn find_or_create<T: 'static>(
+  owner: Option<Owner>,
+  signals: StoredValue<Vec<(TypeId, RwSignal<T>)>>,
+  id: TypeId,
+  init: impl FnOnce() -> T,
+) -> RwSignal<T> {
+  let mut signal = None;
+
+  signals.update_value(|signals| {
+    signal = Some(
+      if let Some((_, signal)) =
+        signals.iter().find(|(type_id, _)| *type_id == id)
+      {
+        *signal
+      } else {
+        let signal = match owner {
+          Some(owner) => with_owner(owner, || create_rw_signal(init())),
+          None => create_rw_signal(init()),
+        };
+
+        signals.push((id, signal));
+
+        signal
+      },
+    );
+  });
+
+  signal.unwrap()
+}
+
+thread_local! {
+  /// The content of every portal under a [`PortalProvider`] which persists,
+  /// which outlives the providers themselves.
+  static PERSISTED_PORTALS: RefCell<Vec<(TypeId, ChildrenFn)>> =
+    RefCell::new(Vec::new());
+}
+
+/// Keeps `children` as the persisted content of `id`, or forgets it if
+/// `None`.
+fn persist_content(id: TypeId, children: Option<ChildrenFn>) {
+  PERSISTED_PORTALS.with(|portals| {
+    let mut portals = portals.borrow_mut();
+
+    portals.retain(|(type_id, _)| *type_id != id);
+
+    if let Some(children) = children {
+      portals.push((id, children));
+    }
+  });
+}
+
+/// Returns the persisted content of `id`, if any.
+fn persisted_content(id: TypeId) -> Option<ChildrenFn> {
+  PERSISTED_PORTALS.with(|portals| {
+    portals
+      .borrow()
+      .iter()
+      .find(|(type_id, _)| *type_id == id)
+      .map(|(_, children)| children.clone())
+  })
+}
+
+/// Gets the [`PortalCtx`] provided by the nearest [`PortalProvider`].
+///
+/// In the browser, if there is no [`PortalProvider`], a default registry
+/// shared by the whole app is used instead, so that simple apps do not need
+/// one. An explicit [`PortalProvider`] always takes precedence, which keeps
+/// its portals isolated from the default registry.
+///
+/// The default registry belongs to the component which first used it, and is
+/// replaced by an empty one if that component unmounts. Apps whose portal
+/// components come and go should therefore use a [`PortalProvider`].
+///
+/// On the server, a thread serves many requests, and a shared registry would
+/// leak content between them, so a [`PortalProvider`] is always required.
+pub fn use_portal_ctx() -> Result<PortalCtx, PortalError> {
+  if let Some(portal_ctx) = use_context::<PortalCtx>() {
+    return Ok(portal_ctx);
+  }
+
+  if leptos_dom::is_browser() {
+    return Ok(default_portal_ctx());
+  }
+
+  Err(PortalError::NoProvider)
+}
+
+/// Returns the registry used in the browser when there is no
+/// [`PortalProvider`].
+fn default_portal_ctx() -> PortalCtx {
+  thread_local! {
+    static DEFAULT_PORTAL_CTX: Cell<Option<PortalCtx>> = const { Cell::new(None) };
+  }
+
+  DEFAULT_PORTAL_CTX.with(|default| match default.get() {
+    Some(portal_ctx) if portal_ctx.is_alive() => portal_ctx,
+    _ => {
+      let portal_ctx = PortalCtx::new(
+        false,
+        Vec::new(),
+        None,
+        Signal::derive(|| true),
+        false,
+        0,
+      );
+
+      default.set(Some(portal_ctx));
+
+      portal_ctx
+    }
+  })
+}
+
+/// Declares portal ids and renders its body under its own [`PortalProvider`],
+/// so that the portals of a self-contained widget do not leak into, or clash
+/// with, the portals of the app using it.
+///
+/// The ids are unit structs local to the block, and the body is an
+/// expression producing the view, usually a `view!`.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[component]
+/// fn Dialog() -> impl IntoView {
+///   portal_scope! {
+///     id: Title, Actions;
+///     provider {
+///       view! {
+///         <div class="dialog">
+///           <PortalInput id=Title>"Are you sure?"</PortalInput>
+///           <PortalInput id=Actions><button>"Yes"</button></PortalInput>
+///
+///           <h2><PortalOutput id=Title /></h2>
+///           <footer><PortalOutput id=Actions /></footer>
+///         </div>
+///       }
+///     }
+///   }
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| view! { <Dialog /> });
+///
+/// assert!(html.contains("Are you sure?"));
+/// ```
+#[macro_export]
+macro_rules! portal_scope {
+  (id: $($id:ident),+ $(,)?; provider $body:block) => {{
+    $(
+      struct $id;
+    )+
+
+    $crate::portal::PortalProvider(
+      $crate::portal::PortalProviderProps::builder()
+        .children(::std::boxed::Box::new(move || {
+          ::leptos::Fragment::new(::std::vec![::leptos::IntoView::into_view(
+            $body,
+          )])
+        }))
+        .build(),
+    )
+  }};
+}
+
+/// The portal provider which allows to use [`PortalInput`] and [`PortalOutput`].
+///
+/// This must be located somewhere near the root of your component tree, above
+/// anywhere you would like to use portals. It is optional in apps which only
+/// render in the browser, please refer to [`use_portal_ctx`] for details.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct PortalId;
+///
+/// view! {
+///   <PortalProvider>
+///     <div>
+///       <h1>"Portal goes here!"</h1>
+///       <PortalOutput id=PortalId />
+///     </div>
+///
+///     <PortalInput id=PortalId>
+///       <p>"I went through the portal!"</p>
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Default crossfade
+///
+/// A `crossfade` set on the provider applies to every output below it, for a
+/// consistent transition style across the app. An output setting its own
+/// `crossfade` uses it instead, and one setting `no_crossfade` does not
+/// crossfade at all.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct Sidebar;
+/// struct Modal;
+/// struct Tooltip;
+///
+/// view! {
+///   <PortalProvider crossfade="fade">
+///     // Crossfades with the `fade` classes
+///     <PortalOutput id=Sidebar />
+///     // Crossfades with the `zoom` classes
+///     <PortalOutput id=Modal crossfade="zoom" />
+///     // Does not crossfade
+///     <PortalOutput id=Tooltip no_crossfade=true />
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Disabling outputs
+///
+/// While `enabled` is false, e.g. during a full-screen takeover, every output
+/// below the provider renders nothing. Inputs still register their content,
+/// which outputs show again once `enabled` is true again.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Toast;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider enabled=false>
+///     <PortalInput id=Toast>"Saved!"</PortalInput>
+///     <PortalOutput id=Toast />
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(!html.contains("Saved!"));
+/// ```
+///
+/// ### Persisting content
+///
+/// Content normally belongs to the provider, and is lost when the provider
+/// is remounted, e.g. when it is inside a route. With `persist`, content is
+/// also kept in a registry which outlives providers, and a provider which
+/// persists starts out with the content already in it, so overlays stay
+/// open across remounts:
+///
+/// - Registering content, and [`clear_portal`], update the registry.
+/// - Inputs leave their content in the portal when they unmount, except
+///   with `once`, `ids` or `fallbacks`, which empty the mounted portal but
+///   leave the content in the registry, to be shown again on the next
+///   mount. Content therefore lives until it is replaced or cleared with
+///   [`clear_portal`], not until its input unmounts.
+/// - Content is not re-owned by the provider which shows it again. Signals
+///   it reads which were created below the previous provider, e.g. in the
+///   component of its input, are disposed of along with that provider, and
+///   no longer update. Read state which outlives the provider instead, such
+///   as state provided above it.
+/// - The registry is shared by every provider which persists, in the same
+///   thread.
+/// - Content is not persisted on the server, where the registry would outlive
+///   requests.
+///
+/// ### Priority offsets
+///
+/// A provider's `priority_offset` is added to the priority of every input
+/// below it, so that an embedded widget with a provider of its own can rank
+/// all of its portals below, with a negative offset, or above, with a
+/// positive one, those of the host app.
+///
+/// Offsets combine by addition across nesting levels: a provider with an
+/// offset of `-15` inside one with an offset of `10` gives its inputs an
+/// offset of `-5`, as reported by [`PortalCtx::priority_offset`]. A provider
+/// without `priority_offset` therefore keeps the offset of the provider above
+/// it, rather than resetting it to `0`.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// #[component]
+/// fn Offset() -> impl IntoView {
+///   expect_context::<PortalCtx>().priority_offset()
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider priority_offset=10>
+///     <PortalProvider priority_offset=-15>
+///       <PortalProvider>
+///         <Offset />
+///       </PortalProvider>
+///     </PortalProvider>
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains("-5"));
+/// ```
+///
+/// ### Strict mode
+///
+/// Two outputs with the same `id` both render the content of the portal,
+/// which is usually a bug caused by reusing an id by accident. With `strict`,
+/// mounting such an output panics in debug builds, while release builds are
+/// unaffected.
+///
+/// Outputs which are meant to show the same content, such as a title shown
+/// both in the header and in the window title, opt out by setting
+/// `broadcast`. An output which broadcasts only coexists with other outputs
+/// which broadcast, so an accidental collision with one of them is still
+/// caught.
+/// ```rust,should_panic
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Title;
+///
+/// leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider strict=true>
+///     <PortalInput id=Title>"Settings"</PortalInput>
+///     <header><PortalOutput id=Title broadcast=true /></header>
+///     <main><PortalOutput id=Title /></main>
+///   </PortalProvider>
+///   }
+/// });
+/// ```
+#[component]
+pub fn PortalProvider(
+  /// The rest of your app. [`PortalInput`] and [`PortalOutput`] can be used
+  /// anywhere below this point.
+  children: Children,
+  /// In debug builds, panics when two outputs with the same `id` are mounted
+  /// at the same time, which is usually an accidental id collision, unless
+  /// they both set `broadcast`.
+  #[prop(optional)]
+  strict: bool,
+  /// Ids which resolve to another id, please refer to [`PortalAlias`].
+  #[prop(optional)]
+  aliases: Vec<PortalAlias>,
+  /// The `crossfade` of every [`PortalOutput`] below which does not set its
+  /// own, or opt out with `no_crossfade`.
+  #[prop(optional)]
+  crossfade: Option<&'static str>,
+  /// Whether outputs render content. While false, every output below renders
+  /// nothing, while the content registered by inputs is kept, and shown again
+  /// once this is true again.
+  #[prop(default = true.into(), into)]
+  enabled: MaybeSignal<bool>,
+  /// Keeps the content of every portal in a registry which outlives this
+  /// provider, so that content survives the provider being remounted, please
+  /// refer to [`PortalProvider`].
+  #[prop(optional)]
+  persist: bool,
+  /// Added to the priority of every input below this provider, on top of the
+  /// `priority_offset` of the providers above it, so that the portals of an
+  /// embedded widget rank consistently below or above those of its host.
+  #[prop(optional)]
+  priority_offset: i32,
+) -> impl IntoView {
+  // Offsets accumulate through nested providers
+  let priority_offset = use_context::<PortalCtx>()
+    .map_or(0, |parent| parent.priority_offset)
+    + priority_offset;
+
+  provide_context(PortalCtx::new(
+    strict,
+    aliases,
+    crossfade,
+    Signal::derive(move || enabled.get()),
+    persist,
+    priority_offset,
+  ));
+
+  children()
+}
+
+/// Renders a floating panel listing every portal id used by an input or an
+/// output under the [`PortalProvider`], with whether the portal has content,
+/// and how many inputs and outputs of it are mounted, all kept up to date.
+///
+/// This is a development aid for tracking down content ending up in the
+/// wrong output, or in none. It only exists with the `devtools` feature, so
+/// that it cannot ship by accident: enable the feature in development builds
+/// only.
+///
+/// Ids are listed by the name of their type. Ids only ever used through
+/// [`PortalCtx`] directly, rather than by a component, are not listed.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Toast;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalInput id=Toast>"Saved!"</PortalInput>
+///     <PortalDebugOverlay />
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains("Toast"));
+/// ```
+#[cfg(feature = "devtools")]
+#[component]
+pub fn PortalDebugOverlay() -> impl IntoView {
+  let portal_ctx = use_portal_ctx()
+    .unwrap_or_else(|err| panic!("`<PortalDebugOverlay />`: {err}"));
+
+  let rows = move || {
+    portal_ctx
+      .named_ids
+      .get()
+      .into_iter()
+      .map(|(id, name)| {
+        let has_content = portal_ctx.get(id).with(Option::is_some);
+        let inputs = portal_ctx.input_count(id);
+        let outputs = portal_ctx.output_count(id);
+
+        view! {
+          <tr>
+            <td>{name}</td>
+            <td>{if has_content { "yes" } else { "no" }}</td>
+            <td>{inputs}</td>
+            <td>{outputs}</td>
+          </tr>
+        }
+      })
+      .collect_view()
+  };
+
+  view! {
+    <aside
+      aria-label="Portals"
+      style="position: fixed; right: 8px; bottom: 8px; z-index: 2147483647; \
+             padding: 8px; background: rgba(0, 0, 0, 0.8); color: white; \
+             font: 12px monospace; pointer-events: none"
+    >
+      <table>
+        <thead>
+          <tr>
+            <th>"Portal"</th>
+            <th>"Content"</th>
+            <th>"Inputs"</th>
+            <th>"Outputs"</th>
+          </tr>
+        </thead>
+        <tbody>{rows}</tbody>
+      </table>
+    </aside>
+  }
+}
+
+/// Makes the portal id `From` resolve to the portal id `To` under a
+/// [`PortalProvider`], so that inputs of `From` render in the outputs of `To`.
+///
+/// This allows funneling several legacy ids into a single output without
+/// rewriting every input.
+///
+/// An alias applies to every use of `From`, not only to inputs: an output of
+/// `From` renders the content of `To` as well, and content registered for
+/// `From` and for `To` replace each other, as if they were the same id.
+/// Aliases are not followed transitively, so an alias of an alias does not
+/// resolve to the final id. If `From` has several aliases, the first one
+/// wins.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct LegacyToast;
+/// struct LegacyBanner;
+/// struct Notifications;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider aliases=vec![
+///     PortalAlias::new::<LegacyToast, Notifications>(),
+///     PortalAlias::new::<LegacyBanner, Notifications>(),
+///   ]>
+///     <PortalInput id=LegacyToast>"Saved!"</PortalInput>
+///     <PortalOutput id=Notifications />
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains("Saved!"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PortalAlias {
+  from: TypeId,
+  to: TypeId,
+}
+
+impl PortalAlias {
+  /// Creates an alias resolving the portal id `From` to the portal id `To`.
+  pub fn new<From: Any, To: Any>() -> Self {
+    Self {
+      from: TypeId::of::<From>(),
+      to: TypeId::of::<To>(),
+    }
+  }
+}
+
+/// The portal entry point. Whatever children this component has will be rendered
+/// in the corresponding [`PortalOutput`] with the matching `id`, wherever in your
+/// app that may be.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct PortalId;
+///
+/// view! {
+///   <PortalProvider>
+///     <div>
+///       <h1>"Portal goes here!"</h1>
+///       <PortalOutput id=PortalId />
+///     </div>
+///
+///     <PortalInput id=PortalId>
+///       <p>"I went through the portal!"</p>
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Single-render content
+///
+/// With `once`, only the first [`PortalInput`] to reach an empty portal is
+/// rendered, until it unmounts.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct PortalId;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalInput id=PortalId once=true>"First"</PortalInput>
+///     <PortalInput id=PortalId once=true>"Second"</PortalInput>
+///     <PortalOutput id=PortalId />
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains("First"));
+/// assert!(!html.contains("Second"));
+/// ```
+///
+/// ### Deduplicating content
+///
+/// With `dedup_key`, an input whose key matches that of the content already
+/// in the portal leaves it alone, e.g. so that a toast enqueued twice in a
+/// row is only shown once.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Toast;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalInput id=Toast dedup_key="saved">"Saved!"</PortalInput>
+///     <PortalInput id=Toast dedup_key="saved">"Saved again!"</PortalInput>
+///     <PortalOutput id=Toast />
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains("Saved!"));
+/// assert!(!html.contains("Saved again!"));
+/// ```
+///
+/// ### Refreshing content
+///
+/// Outputs call the children when they are registered, and keep the view
+/// they built. Signals read by the children keep that view up to date, as
+/// usual, which is all most content needs. Only children reading state which
+/// is not reactive, e.g. a value behind a [`RefCell`](std::cell::RefCell),
+/// need `refresh`, a [`Trigger`] which renders them again in the outputs
+/// whenever it is notified.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::{
+///   cell::Cell,
+///   rc::Rc,
+/// };
+///
+/// struct Status;
+///
+/// # let runtime = create_runtime();
+/// let saved_at = Rc::new(Cell::new(0));
+/// let refresh = create_trigger();
+///
+/// let shown_saved_at = saved_at.clone();
+///
+/// view! {
+///   <PortalProvider>
+///     <PortalOutput id=Status />
+///
+///     <PortalInput id=Status refresh=refresh>
+///       "Last saved at " {shown_saved_at.get()}
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+///
+/// saved_at.set(42);
+/// refresh.notify();
+/// # runtime.dispose();
+/// ```
+///
+/// ### Fallback chain
+///
+/// With `fallbacks`, the children go to the first portal of the chain which
+/// has a mounted output, and with `inline_fallback`, they are rendered in
+/// place if none has. This suits layouts where some regions are optional.
+///
+/// The chain is reactive: when an output earlier in the chain mounts, the
+/// children move to it, and when the output they are rendered in unmounts,
+/// they move to the next one still mounted.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::any::TypeId;
+///
+/// struct Sidebar;
+/// struct Drawer;
+///
+/// // Neither a sidebar nor a drawer in this layout
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalInput
+///       id=Sidebar
+///       fallbacks=vec![TypeId::of::<Drawer>()]
+///       inline_fallback=true
+///     >
+///       "Filters"
+///     </PortalInput>
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains("Filters"));
+/// ```
+///
+/// ### Several portals
+///
+/// With `ids`, the children are also rendered in the outputs of the listed
+/// portals, e.g. a cart summary shown both in the header and in a sidebar.
+/// Each output renders its own copy of the children.
+///
+/// When the input unmounts, each of the portals it targets is emptied,
+/// unless another input has replaced the content there since.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::any::TypeId;
+///
+/// struct Header;
+/// struct Sidebar;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalInput id=Header ids=vec![TypeId::of::<Sidebar>()]>
+///       "3 items"
+///     </PortalInput>
+///
+///     <header><PortalOutput id=Header /></header>
+///     <aside><PortalOutput id=Sidebar /></aside>
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert_eq!(html.matches("3 items").count(), 2);
+/// ```
+#[component]
+pub fn PortalInput<T>(
+  /// The type used as an `id`. This must match the `id` of the
+  /// corresponding [`PortalOutput`].
+  id: T,
+  /// The children you want to render anywhere the matching [`PortalOutput`]
+  /// is located.
+  children: ChildrenFn,
+  /// Only registers the children if the portal is empty, ignoring any
+  /// [`PortalInput`] rendered for the same `id` afterwards, for as long as
+  /// this one is mounted. The portal is emptied when it unmounts.
+  ///
+  /// This is useful for content which should be handed over exactly once,
+  /// such as a snapshot of a measured value, when the parent of this
+  /// component re-renders often.
+  #[prop(optional)]
+  once: bool,
+  /// The ids of other portals to render the children in, in order, when no
+  /// output of `id` is mounted, e.g. `vec![TypeId::of::<Sidebar>()]`.
+  ///
+  /// The children are rendered in the first of `id` and `fallbacks` which
+  /// has a mounted output, moving as outputs mount and unmount. The portal
+  /// they are rendered in is emptied when this component unmounts. `once` is
+  /// ignored with a fallback chain.
+  #[prop(optional)]
+  fallbacks: Vec<TypeId>,
+  /// Renders the children in place of this component when no output of `id`
+  /// or of any of `fallbacks` is mounted.
+  #[prop(optional)]
+  inline_fallback: bool,
+  /// The ids of other portals to render the children in as well as `id`,
+  /// e.g. `vec![TypeId::of::<Sidebar>()]`.
+  ///
+  /// The children always replace the content of these portals: `once`,
+  /// `dedup_key` and `fallbacks` only apply to `id`. When this component
+  /// unmounts, these portals, and `id` unless one of those is set, are
+  /// emptied, unless another input has replaced the content since.
+  #[prop(optional)]
+  ids: Vec<TypeId>,
+  /// Does not register the children if the content currently in the portal
+  /// was registered with the same key, e.g. by another [`PortalInput`] for
+  /// the same toast, so that it is not rendered again. Inputs without a key
+  /// always replace the content. `once` is ignored with a `dedup_key`.
+  #[prop(optional, into)]
+  dedup_key: Option<String>,
+  /// Renders the children again in the outputs whenever this is notified,
+  /// while they are the content of the portal.
+  ///
+  /// Outputs only call the children when they are registered, so this is
+  /// only needed when the children read state which is not reactive, please
+  /// refer to [`PortalInput`].
+  #[prop(optional)]
+  refresh: Option<Trigger>,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
+) -> impl IntoView
+where
+  T: Any,
+{
+  let portal_ctx = use_portal_ctx().unwrap_or_else(|err| {
+    panic!("{}: {err}", component_name("PortalInput", debug_name))
+  });
+
+  let id = portal_ctx.id_of(&id);
+
+  portal_ctx.count_input(id);
+
+  for id in &ids {
+    portal_ctx.count_input(*id);
+  }
+
+  if let Some(refresh) = refresh {
+    let ids = [id]
+      .into_iter()
+      .chain(fallbacks.iter().copied())
+      .chain(ids.iter().copied())
+      .collect::<Vec<_>>();
+    let children = children.clone();
+
+    create_effect(move |refreshed: Option<()>| {
+      refresh.track();
+
+      if refreshed.is_some() {
+        for id in &ids {
+          portal_ctx.refresh_if_registered(*id, &children);
+        }
+      }
+    });
+  }
+
+  let targets_several = !ids.is_empty();
+
+  if targets_several {
+    for id in &ids {
+      portal_ctx.register(*id, children.clone());
+    }
+
+    let children = children.clone();
+
+    on_cleanup(move || {
+      for id in ids {
+        portal_ctx.clear_if_registered(id, &children);
+      }
+    });
+  }
+
+  if !fallbacks.is_empty() || inline_fallback {
+    let ids = [id].into_iter().chain(fallbacks).collect();
+
+    return register_with_fallbacks(portal_ctx, ids, inline_fallback, children);
+  }
+
+  if let Some(key) = dedup_key {
+    portal_ctx.register_dedup(id, key, children);
+  } else if !once {
+    if targets_several {
+      let children = children.clone();
+
+      on_cleanup(move || portal_ctx.clear_if_registered(id, &children));
+    }
+
+    portal_ctx.register(id, children);
+  } else if portal_ctx.register_once(id, children) {
+    on_cleanup(move || portal_ctx.clear_mounted(id));
+  }
+
+  ().into_view()
+}
+
+/// Registers `children` for the first of `ids` which has a mounted output,
+/// following outputs as they mount and unmount, and renders them in place
+/// when there is none if `inline` is set.
+fn register_with_fallbacks(
+  portal_ctx: PortalCtx,
+  ids: Vec<TypeId>,
+  inline: bool,
+  children: ChildrenFn,
+) -> View {
+  let target = create_memo(move |_| {
+    ids
+      .iter()
+      .copied()
+      .find(|id| portal_ctx.output_count(*id).get() > 0)
+  });
+
+  create_isomorphic_effect({
+    let children = children.clone();
+
+    move |previous: Option<Option<TypeId>>| {
+      if let Some(Some(previous)) = previous {
+        portal_ctx.clear_if_registered(previous, &children);
+      }
+
+      let target = target.get();
+
+      if let Some(target) = target {
+        portal_ctx.register(target, children.clone());
+      }
+
+      target
+    }
+  });
+
+  on_cleanup({
+    let children = children.clone();
+
+    move || {
+      if let Some(target) = target.try_get_untracked().flatten() {
+        portal_ctx.clear_if_registered(target, &children);
+      }
+    }
+  });
+
+  (move || (inline && target.get().is_none()).then(|| children())).into_view()
+}
+
+/// Like [`PortalInput`], but the children are built once, when this
+/// component is rendered, so they can move owned values which are not
+/// [`Clone`] into the content, such as the state of a modal.
+///
+/// [`PortalInput`] children are called every time an output renders them,
+/// which is what allows several outputs, and remounted outputs, to each
+/// render their own copy. Content built once can only be in one place at a
+/// time instead, so:
+///
+/// - With a single [`PortalOutput`], including with `crossfade` or `history`,
+///   the content behaves as with [`PortalInput`].
+/// - With several outputs of the same `id`, e.g. outputs which `broadcast`,
+///   the content is only shown by the output which rendered it last. Use
+///   [`PortalInput`] for content shown in several places.
+///
+/// When this component unmounts, the portal is emptied, unless another input
+/// has replaced its content since.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Modal;
+///
+/// // Not `Clone`
+/// struct Draft {
+///   text: String,
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   let draft = Draft {
+///     text: "Unsent message".to_string(),
+///   };
+///
+///   view! {
+///   <PortalProvider>
+///     <MovePortalInput id=Modal>
+///       <p>{draft.text}</p>
+///     </MovePortalInput>
+///     <PortalOutput id=Modal />
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains("Unsent message"));
+/// ```
+#[component]
+pub fn MovePortalInput<T>(
+  /// The type used as an `id`. This must match the `id` of the
+  /// corresponding [`PortalOutput`].
+  id: T,
+  /// The children you want to render where the matching [`PortalOutput`] is
+  /// located, which are only called once.
+  children: Children,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
+) -> impl IntoView
+where
+  T: Any,
+{
+  let portal_ctx = use_portal_ctx().unwrap_or_else(|err| {
+    panic!("{}: {err}", component_name("MovePortalInput", debug_name))
+  });
+
+  let id = portal_ctx.id_of(&id);
+
+  portal_ctx.count_input(id);
+
+  let content = children().into_view();
+
+  let children: ChildrenFn =
+    Rc::new(move || Fragment::new(vec![content.clone()]));
+
+  portal_ctx.register(id, children.clone());
+
+  on_cleanup(move || portal_ctx.clear_if_registered(id, &children));
+}
+
+/// Like [`PortalInput`], but the children are produced by an async function,
+/// with `fallback` rendered at the matching [`PortalOutput`] until it
+/// resolves.
+///
+/// The future is run once, when this component is rendered. When this
+/// component unmounts, the portal is emptied, unless another input has
+/// replaced its content since, and the result of a future which has not
+/// resolved yet is ignored.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Modal;
+///
+/// async fn invoice_details() -> impl IntoView {
+///   view! { <p>"Invoice #42"</p> }
+/// }
+///
+/// # let runtime = create_runtime();
+/// view! {
+///   <PortalProvider>
+///     <PortalOutput id=Modal />
+///
+///     <AsyncPortalInput
+///       id=Modal
+///       future=invoice_details
+///       fallback=|| "Loading..."
+///     />
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn AsyncPortalInput<T, F, Fut, V>(
+  /// The type used as an `id`. This must match the `id` of the
+  /// corresponding [`PortalOutput`].
+  id: T,
+  /// The async function producing the children to render wherever the
+  /// matching [`PortalOutput`] is located.
+  future: F,
+  /// What to render at the output while `future` has not resolved yet.
+  /// Nothing is rendered by default.
+  #[prop(optional, into)]
+  fallback: ViewFn,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
+) -> impl IntoView
+where
+  T: Any,
+  F: Fn() -> Fut + 'static,
+  Fut: Future<Output = V> + 'static,
+  V: IntoView,
+{
+  let portal_ctx = use_portal_ctx().unwrap_or_else(|err| {
+    panic!("{}: {err}", component_name("AsyncPortalInput", debug_name))
+  });
+
+  let id = portal_ctx.id_of(&id);
+
+  portal_ctx.count_input(id);
+
+  let content = create_local_resource(
+    || (),
+    move |_| {
+      let content = future();
+
+      async move { content.await.into_view() }
+    },
+  );
+
+  let children: ChildrenFn = Rc::new(move || {
+    let fallback = fallback.clone();
+
+    Fragment::new(vec![(move || {
+      content.get().unwrap_or_else(|| fallback.run())
+    })
+    .into_view()])
+  });
+
+  portal_ctx.register(id, children.clone());
+
+  on_cleanup(move || portal_ctx.clear_if_registered(id, &children));
+}
+
+/// The non-panicking version of [`PortalInput`], for when a missing
+/// [`PortalProvider`] should be handled rather than treated as a bug.
+pub fn try_portal_input<T>(
+  id: T,
+  children: ChildrenFn,
+) -> Result<(), PortalError>
+where
+  T: Any,
+{
+  let portal_ctx = use_portal_ctx()?;
+  let id = portal_ctx.id_of(&id);
+
+  portal_ctx.count_input(id);
+  portal_ctx.register(id, children);
+
+  Ok(())
+}
+
+/// Returns a signal to the number of [`PortalInput`]s currently mounted for
+/// the portal `T`, e.g. to show how many notifications are pending.
+///
+/// Content set with [`set_portal`] is not an input, and is not counted.
+///
+/// # Panics
+/// Panics if there is no [`PortalProvider`] and no default registry, please
+/// refer to [`use_portal_ctx`].
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Notifications;
+///
+/// #[component]
+/// fn Badge() -> impl IntoView {
+///   let count = use_portal_count::<Notifications>();
+///
+///   view! { <span class="badge">{count}</span> }
+/// }
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalInput id=Notifications>"Saved!"</PortalInput>
+///     <PortalInput id=Notifications>"Synced!"</PortalInput>
+///     <Badge />
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.contains(">2<"));
+/// ```
+pub fn use_portal_count<T>() -> Signal<usize>
+where
+  T: Any,
+{
+  let portal_ctx = use_portal_ctx()
+    .unwrap_or_else(|err| panic!("`use_portal_count()`: {err}"));
+
+  portal_ctx.input_count(TypeId::of::<T>())
+}
+
+/// Imperatively sets the content of the portal `T`, e.g. from an effect, as
+/// if it came from a [`PortalInput`].
+///
+/// Content set this way and content from a [`PortalInput`] with the same `id`
+/// replace each other, so whichever was set last is rendered.
+///
+/// Like any context, the [`PortalProvider`] can only be found while a
+/// component or effect below it is running. In event handlers, get the
+/// [`PortalCtx`] with [`use_portal_ctx`] when building the component and call
+/// [`PortalCtx::register`] instead.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct Toast;
+///
+/// #[component]
+/// fn ErrorToast(error: ReadSignal<Option<String>>) -> impl IntoView {
+///   create_effect(move |_| {
+///     if let Some(error) = error.get() {
+///       let _ = set_portal::<Toast, _>(move || error.clone());
+///     } else {
+///       let _ = clear_portal::<Toast>();
+///     }
+///   });
+/// }
+///
+/// let (error, _) = create_signal(None::<String>);
+///
+/// view! {
+///   <PortalProvider>
+///     <PortalOutput id=Toast />
+///     <ErrorToast error=error />
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+pub fn set_portal<T, IV>(
+  children: impl Fn() -> IV + 'static,
+) -> Result<(), PortalError>
+where
+  T: Any,
+  IV: IntoView,
+{
+  let portal_ctx = use_portal_ctx()?;
+
+  portal_ctx.register(
+    TypeId::of::<T>(),
+    Rc::new(move || Fragment::new(vec![children().into_view()])),
+  );
+
+  Ok(())
+}
+
+/// Imperatively empties the portal `T`, whether its content was set by
+/// [`set_portal`] or a [`PortalInput`].
+pub fn clear_portal<T>() -> Result<(), PortalError>
+where
+  T: Any,
+{
+  let portal_ctx = use_portal_ctx()?;
+
+  portal_ctx.clear(TypeId::of::<T>());
+
+  Ok(())
+}
+
+/// The portal output point. Whatever children the corresponding [`Portal`} with
+/// matching `id` has, will be rendered here.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct PortalId;
+///
+/// view! {
+///   <PortalProvider>
+///     <div>
+///       <h1>"Portal goes here!"</h1>
+///       <PortalOutput id=PortalId />
+///     </div>
+///
+///     <PortalInput id=PortalId>
+///       <p>"I went through the portal!"</p>
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Measuring the content
+///
+/// `on_measure` reports the size and position of the content whenever its
+/// size changes, e.g. to reserve space for it or to position something next
+/// to it.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct PortalId;
+///
+/// let (height, set_height) = create_signal(0.0);
+///
+/// view! {
+///   <PortalProvider>
+///     <PortalOutput
+///       id=PortalId
+///       on_measure=move |rect: Rect| set_height.set(rect.height)
+///     />
+///     <p>"The portal is " {height} "px tall."</p>
+///
+///     <PortalInput id=PortalId>
+///       <p>"I went through the portal!"</p>
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Debouncing content
+///
+/// With `debounce_ms`, content which changes many times a second is rendered
+/// at most once per interval, and always ends up showing the latest content.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct Details;
+///
+/// let (price, _) = create_signal(42.0);
+///
+/// view! {
+///   <PortalProvider>
+///     <PortalOutput id=Details debounce_ms=250 />
+///
+///     <PortalInput id=Details>
+///       <p>"Last price: " {price}</p>
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+///
+/// ### Default content
+///
+/// Children of the output are always rendered, with the portalled content
+/// placed after them, or before them with `placement`. This allows a layout
+/// region to have content of its own which portals add to.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Toolbar;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalInput id=Toolbar>
+///       <button>"Export"</button>
+///     </PortalInput>
+///
+///     <PortalOutput id=Toolbar placement=PortalPlacement::Before>
+///       <button>"Help"</button>
+///     </PortalOutput>
+///   </PortalProvider>
+///   }
+/// });
+///
+/// assert!(html.find("Export") < html.find("Help"));
+/// ```
+///
+/// ### Crossfading
+///
+/// With `crossfade`, a replaced content is kept until its transition ends.
+/// It is removed once its `transition-duration` has passed even when no
+/// `transitionend` event arrives, e.g. when the leave class has no
+/// transition, and at once when motion is not allowed or outside the
+/// browser, so contents swapped repeatedly do not pile up.
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use std::{
+///   any::TypeId,
+///   rc::Rc,
+///   sync::atomic::{
+///     AtomicUsize,
+///     Ordering,
+///   },
+/// };
+///
+/// struct Toast;
+///
+/// // Counts the contents which are still kept
+/// static KEPT: AtomicUsize = AtomicUsize::new(0);
+///
+/// struct Kept;
+///
+/// impl Drop for Kept {
+///   fn drop(&mut self) {
+///     KEPT.fetch_sub(1, Ordering::Relaxed);
+///   }
+/// }
+///
+/// leptos::ssr::render_to_string(|| {
+///   view! {
+///   <PortalProvider>
+///     <PortalOutput id=Toast crossfade="fade" />
+///     {
+///       let portal_ctx = expect_context::<PortalCtx>();
+///
+///       for n in 0..10 {
+///         KEPT.fetch_add(1, Ordering::Relaxed);
+///         let kept = Kept;
+///
+///         portal_ctx.register(
+///           TypeId::of::<Toast>(),
+///           Rc::new(move || {
+///             let _ = &kept;
+///
+///             Fragment::new(vec![n.into_view()])
+///           }),
+///         );
+///       }
+///
+///       // Only the latest content is kept
+///       assert_eq!(KEPT.load(Ordering::Relaxed), 1);
+///     }
+///   </PortalProvider>
+///   }
+/// });
+/// ```
+#[component]
+pub fn PortalOutput<T>(
+  /// The type used as an `id`. This must match the `id` of the
+  /// corresponding [`PortalInput`].
+  id: T,
+  /// Called with the bounding rect of the content when it is first rendered
+  /// and whenever its size changes, in the browser only.
+  ///
+  /// The content is then wrapped in a `<div>`, which is what gets measured.
+  #[prop(optional, into)]
+  on_measure: Option<Callback<Rect>>,
+  /// Keeps every content the portal receives in a [`PortalHistory`], which
+  /// controls which of them is rendered, rather than always rendering the
+  /// latest one.
+  #[prop(optional, into)]
+  history: Option<PortalHistory>,
+  /// Lets `export` read the HTML of the content this output renders, e.g. to
+  /// print just this panel, please refer to [`PortalExport`].
+  ///
+  /// The content is then wrapped in a `<div>` with `display: contents`, which
+  /// is what the HTML is read from.
+  #[prop(optional)]
+  export: Option<PortalExport>,
+  /// Crossfades between contents when one replaces another, using CSS
+  /// classes derived from the given name, e.g. `"fade"`:
+  ///
+  /// - Each content is wrapped in a `<div>` with the `fade` class.
+  /// - A new content replacing another starts with the `fade-enter` class,
+  ///   which is removed on the next frame.
+  /// - The replaced content is kept with the `fade-leave` class until its
+  ///   `transitionend` event, or until its `transition-duration` and
+  ///   `transition-delay` have passed if no such event arrives.
+  ///
+  /// Contents are swapped immediately when motion is not allowed, unless
+  /// `force_motion` is set, please refer to [`use_motion_allowed`].
+  ///
+  /// Both contents are mounted at the same time during the transition, so
+  /// they usually need to be stacked on top of each other with CSS.
+  ///
+  /// Defaults to the `crossfade` of the [`PortalProvider`], if any.
+  #[prop(optional)]
+  crossfade: Option<&'static str>,
+  /// Crossfades even when the user has asked for reduced motion, for
+  /// crossfades which convey meaning.
+  #[prop(optional, into)]
+  force_motion: MaybeSignal<bool>,
+  /// Does not crossfade, even if the [`PortalProvider`] sets a default
+  /// `crossfade`.
+  #[prop(optional)]
+  no_crossfade: bool,
+  /// Content rendered by the output whether or not the portal has content.
+  #[prop(optional)]
+  children: Option<Children>,
+  /// Where the content of the portal is placed relative to `children`.
+  #[prop(optional)]
+  placement: PortalPlacement,
+  /// Reserves at least this height, any CSS length such as `"120px"`, with an
+  /// invisible spacer while the portal has no content, so that content
+  /// arriving later does not shift the layout.
+  #[prop(optional)]
+  reserve_space: Option<&'static str>,
+  /// Applies content changes at most once per this many milliseconds, so
+  /// that content replaced many times a second, e.g. a live-updating detail
+  /// pane, is not rendered each time.
+  ///
+  /// A change is rendered right away if none was in the last interval, or
+  /// else at the end of the interval, with the latest content by then, so the
+  /// latest content is always rendered eventually. Changes are rendered right
+  /// away on the server.
+  #[prop(optional)]
+  debounce_ms: Option<u64>,
+  /// Marks this output as intentionally sharing its `id` with other outputs
+  /// which broadcast, for a strict [`PortalProvider`].
+  #[prop(optional)]
+  broadcast: bool,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
+) -> impl IntoView
+where
+  T: Any,
+{
+  let portal_ctx = use_portal_ctx().unwrap_or_else(|err| {
+    panic!("{}: {err}", component_name("PortalOutput", debug_name))
+  });
+
+  let id = portal_ctx.id_of(&id);
+
+  portal_ctx.count_output(id);
+
+  #[cfg(debug_assertions)]
+  portal_ctx.add_output(
+    id,
+    broadcast,
+    &component_name("PortalOutput", debug_name),
+  );
+  #[cfg(not(debug_assertions))]
+  let _ = broadcast;
+
+  let current = if let Some(history) = history {
+    record_history(history, portal_ctx.get(id))
+  } else {
+    portal_ctx.get(id)
+  };
+
+  let current = if let Some(debounce_ms) = debounce_ms {
+    util::throttle(current, Duration::from_millis(debounce_ms))
+  } else {
+    current
+  };
+
+  let current = portal_ctx.while_enabled(current);
+
+  let crossfade = if no_crossfade {
+    None
+  } else {
+    crossfade.or(portal_ctx.crossfade)
+  };
+
+  let content = if let Some(name) = crossfade {
+    render_crossfade(portal_ctx, id, current, name, force_motion, debug_name)
+      .into_view()
+  } else {
+    render_portal_output(portal_ctx, id, current, debug_name).into_view()
+  };
+
+  let content = if let Some(export) = export {
+    export.follow(current);
+
+    view! {
+      <div node_ref=export.wrapper style="display: contents">{content}</div>
+    }
+    .into_view()
+  } else {
+    content
+  };
+
+  let content = if let Some(min_height) = reserve_space {
+    let spacer =
+      util::reserve_space(min_height, move || current.with(Option::is_none));
+
+    vec![content, spacer].into_view()
+  } else {
+    content
+  };
+
+  let content = if let Some(on_measure) = on_measure {
+    let wrapper = create_node_ref::<html::Div>();
+
+    observe_size(wrapper, on_measure);
+
+    view! { <div node_ref=wrapper>{content}</div> }.into_view()
+  } else {
+    content.into_view()
+  };
+
+  let Some(children) = children else {
+    return content;
+  };
+
+  let children = children().into_view();
+
+  match placement {
+    PortalPlacement::Before => vec![content, children],
+    PortalPlacement::After => vec![children, content],
+  }
+  .into_view()
+}
+
+/// Where a [`PortalOutput`] places the content of its portal relative to its
+/// own children.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PortalPlacement {
+  /// The content of the portal comes before the children.
+  Before,
+  /// The content of the portal comes after the children.
+  #[default]
+  After,
+}
+
+/// The non-panicking version of [`PortalOutput`], for when a missing
+/// [`PortalProvider`] should be handled rather than treated as a bug.
+pub fn try_portal_output<T>(id: T) -> Result<impl IntoView, PortalError>
+where
+  T: Any,
+{
+  let portal_ctx = use_portal_ctx()?;
+
+  let id = portal_ctx.id_of(&id);
+
+  portal_ctx.count_output(id);
+
+  Ok(render_portal_output(
+    portal_ctx,
+    id,
+    portal_ctx.while_enabled(portal_ctx.get(id)),
+    None,
+  ))
+}
+
+/// Renders the view returned by `f` to HTML, inside a [`PortalProvider`],
+/// with the content of every portal placed in its outputs, wherever the
+/// inputs and outputs are in the tree.
+///
+/// This is meant for snapshot testing pages with portals on the server,
+/// where an output normally only shows content registered by inputs placed
+/// before it, as the HTML is rendered in a single pass over the tree.
+/// Instead, `f` is called twice: the first time registers the content of
+/// every input, and is discarded, while the second time is rendered, with
+/// every output finding its content already registered. `f` must therefore
+/// render the same tree both times, and should not contain a
+/// [`PortalProvider`] of its own.
+///
+/// The HTML is deterministic, and its content is ordered as follows:
+///
+/// - Portal content is placed at its output, not at its input, which renders
+///   nothing.
+/// - Within an output, the content comes after the output's own children,
+///   or before them with [`PortalPlacement::Before`], as in the browser.
+/// - With several inputs for the same `id`, an output placed after some of
+///   them shows the last of those, as in the browser, while an output placed
+///   before all of them shows the last input of the tree.
+///
+/// For unit tests of portals which do not need HTML, please refer to
+/// `with_portal_scope`, which requires the `testing` feature.
+///
+/// Requires the `ssr` feature.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Modal;
+///
+/// let html = render_portals_to_string(|| {
+///   view! {
+///     <main>
+///       <PortalOutput id=Modal />
+///     </main>
+///
+///     <PortalInput id=Modal>
+///       <dialog>"Are you sure?"</dialog>
+///     </PortalInput>
+///   }
+/// });
+///
+/// let dialog = html.find("<dialog").unwrap();
+///
+/// assert!(html.find("<main").unwrap() < dialog);
+/// assert!(dialog < html.find("</main>").unwrap());
+/// ```
+#[cfg(feature = "ssr")]
+pub fn render_portals_to_string<F, N>(f: F) -> String
+where
+  F: Fn() -> N + 'static,
+  N: IntoView,
+{
+  leptos::ssr::render_to_string(move || {
+    view! {
+      <PortalProvider>
+        {
+          // Registers the content of every input, so that the outputs of the
+          // rendered pass find it wherever they are
+          let _ = f().into_view();
+
+          f()
+        }
+      </PortalProvider>
+    }
+  })
+  .to_string()
+}
+
+/// Runs `f` in a reactive runtime of its own, under a fresh portal registry,
+/// so that portal tests run in the same process are isolated from each
+/// other.
+///
+/// Portal ids are types, which every test of a crate shares, and registries
+/// which outlive providers, such as the one of a [`PortalProvider`] which
+/// persists, are kept per thread. Within `f`, portals use a registry of their
+/// own, as if `f` was rendered under a [`PortalProvider`], and the persisted
+/// registry starts out empty and is restored afterwards. The runtime is
+/// disposed of once `f` returns.
+///
+/// To test the HTML of a page with portals instead, please refer to
+/// `render_portals_to_string`, which requires the `ssr` feature.
+///
+/// Requires the `testing` feature.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// struct Toast;
+///
+/// let count = with_portal_scope(|| {
+///   let _view = view! {
+///     <PortalInput id=Toast>"Saved!"</PortalInput>
+///     <PortalInput id=Toast>"Synced!"</PortalInput>
+///   };
+///
+///   use_portal_count::<Toast>().get_untracked()
+/// });
+///
+/// assert_eq!(count, 2);
+///
+/// // Nothing is left over from the scope above
+/// let count =
+///   with_portal_scope(|| use_portal_count::<Toast>().get_untracked());
+///
+/// assert_eq!(count, 0);
+/// ```
+#[cfg(feature = "testing")]
+pub fn with_portal_scope<T>(f: impl FnOnce() -> T) -> T {
+  let runtime = create_runtime();
+  let persisted = PERSISTED_PORTALS.with(RefCell::take);
+
+  provide_context(PortalCtx::new(
+    false,
+    Vec::new(),
+    None,
+    Signal::derive(|| true),
+    false,
+    0,
+  ));
+
+  let value = f();
+
+  runtime.dispose();
+  PERSISTED_PORTALS.with(|portals| *portals.borrow_mut() = persisted);
+
+  value
+}
+
+/// Renders `content`, which is the content of the portal `id`, or a content
+/// it used to have.
+fn render_portal_output(
+  portal_ctx: PortalCtx,
+  id: TypeId,
+  content: Signal<Option<ChildrenFn>>,
+  debug_name: Option<&'static str>,
+) -> impl IntoView {
+  move || {
+    content.with(|children| {
+      if let Some(children) = children {
+        portal_ctx.render(id, children, debug_name)
+      } else {
+        ().into_view()
+      }
+    })
+  }
+}
+
+/// Records every new content of `children` in `history`, returning the
+/// content `history` currently shows.
+fn record_history(
+  history: PortalHistory,
+  children: Signal<Option<ChildrenFn>>,
+) -> Signal<Option<ChildrenFn>> {
+  create_isomorphic_effect(move |_| {
+    if let Some(children) = children.get() {
+      history.push(children);
+    }
+  });
+
+  Signal::derive(move || history.current())
+}
+
+/// A content of a crossfading [`PortalOutput`].
+#[derive(Clone)]
+struct CrossfadeLayer {
+  key: usize,
+  children: ChildrenFn,
+  /// Whether this content replaced another one, and should therefore fade in.
+  replacing: bool,
+  leaving: bool,
+}
+
+/// Like [`render_portal_output`], but keeps replaced contents mounted with
+/// the `{name}-leave` class until their transition ends, while the new
+/// content starts with the `{name}-enter` class for a frame.
+fn render_crossfade(
+  portal_ctx: PortalCtx,
+  id: TypeId,
+  content: Signal<Option<ChildrenFn>>,
+  name: &'static str,
+  force_motion: MaybeSignal<bool>,
+  debug_name: Option<&'static str>,
+) -> impl IntoView {
+  let layers = create_rw_signal(Vec::<CrossfadeLayer>::new());
+  let next_key = store_value(0);
+
+  let motion_allowed = use_motion_allowed(force_motion);
+
+  create_isomorphic_effect(move |_| {
+    let children = content.get();
+
+    // Without motion, nor outside the browser, there is no transition to
+    // wait for, so replaced contents are removed at once
+    let animate = leptos_dom::is_browser() && motion_allowed.get_untracked();
+
+    layers.update(|layers| {
+      let replacing = animate && layers.iter().any(|layer| !layer.leaving);
+
+      if animate {
+        for layer in layers.iter_mut() {
+          layer.leaving = true;
+        }
+      } else {
+        layers.clear();
+      }
+
+      if let Some(children) = children {
+        let key = next_key.get_value();
+        next_key.set_value(key + 1);
+
+        layers.push(CrossfadeLayer {
+          key,
+          children,
+          replacing,
+          leaving: false,
+        });
+      }
+    });
+  });
+
+  let render_layer = move |layer: CrossfadeLayer| {
+    let key = layer.key;
+
+    let leaving = create_memo(move |_| {
+      layers.with(|layers| {
+        layers
+          .iter()
+          .find(|layer| layer.key == key)
+          .is_none_or(|layer| layer.leaving)
+      })
+    });
+
+    let entering = create_rw_signal(layer.replacing);
+
+    if leptos_dom::is_browser() {
+      request_animation_frame(move || entering.set(false));
+    } else {
+      entering.set(false);
+    }
+
+    let class = move || {
+      if leaving.get() {
+        format!("{name} {name}-leave")
+      } else if entering.get() {
+        format!("{name} {name}-enter")
+      } else {
+        name.to_string()
+      }
+    };
 
-api_planning! {
-  struct PortalA;
-  struct PortalB;
+    let remove = move || {
+      layers.try_update(|layers| layers.retain(|layer| layer.key != key));
+    };
 
-  // Somewhere up there
-  view! { cx,
-    <PortalProvider>
-      // rest of your app
-    </PortalProvider>
+    let on_transition_end = move |event: ev::TransitionEvent| {
+      // Ignore transitions of the content itself, which bubble up to here
+      if event.target() != event.current_target() {
+        return;
+      }
+
+      if leaving.get_untracked() {
+        remove();
+      }
+    };
+
+    // Also removes the content once its transition should have ended, in
+    // case no `transitionend` arrives, e.g. when the leave class has no
+    // transition
+    let wrapper = create_node_ref::<html::Div>();
+
+    create_effect(move |_| {
+      if !leaving.get() {
+        return;
+      }
+
+      request_animation_frame(move || {
+        let timeout = wrapper
+          .get_untracked()
+          .map(|wrapper| transition_timeout(&wrapper))
+          .unwrap_or_default();
+
+        if timeout.is_zero() {
+          remove();
+        } else {
+          set_timeout(remove, timeout);
+        }
+      });
+    });
+
+    view! {
+      <div node_ref=wrapper class=class on:transitionend=on_transition_end>
+        {portal_ctx.render(id, &layer.children, debug_name)}
+      </div>
+    }
+  };
+
+  view! {
+    <For
+      each=move || layers.get()
+      key=|layer| layer.key
+      children=render_layer
+    />
   }
+}
 
-  // Where you want your portal to output
-  view! { cx,
-    <h1>"Where'd these come from???"</h1>
-    <PortalOutput id=PortalA />
-    <PortalOutput id=PortalB />
+/// The contents received by a [`PortalOutput`] given a `history`, along with
+/// which of them is currently shown, allowing to navigate back and forth
+/// between them, e.g. for a detail pane.
+///
+/// New content is always shown as it arrives. As in a browser, receiving new
+/// content after going back discards the contents which were ahead.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct DetailPane;
+///
+/// let history = PortalHistory::new();
+///
+/// view! {
+///   <PortalProvider>
+///     <button
+///       disabled=move || !history.can_go_back()
+///       on:click=move |_| history.back()
+///     >
+///       "Back"
+///     </button>
+///     <button
+///       disabled=move || !history.can_go_forward()
+///       on:click=move |_| history.forward()
+///     >
+///       "Forward"
+///     </button>
+///
+///     <PortalOutput id=DetailPane history=history />
+///
+///     <PortalInput id=DetailPane>
+///       <p>"Details of the selected item."</p>
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+/// # runtime.dispose();
+/// ```
+#[derive(Clone, Copy)]
+pub struct PortalHistory {
+  entries: RwSignal<Vec<ChildrenFn>>,
+  position: RwSignal<usize>,
+}
+
+impl Default for PortalHistory {
+  fn default() -> Self {
+    Self::new()
   }
+}
 
-  // Where you want portal contents to go
-  view! { cx,
-    <PortalInput id=PortalA>
-      <p>"I used a portal to get here..."</p>
-    </PortalInput>
-    <PortalInput id=PortalB>
-      <p>"The cake was really nice"</p>
-      </PortalInput>
+impl PortalHistory {
+  /// Creates an empty history.
+  pub fn new() -> Self {
+    Self {
+      entries: create_rw_signal(Vec::new()),
+      position: create_rw_signal(0),
+    }
+  }
+
+  /// The number of contents in the history.
+  pub fn len(&self) -> usize {
+    self.entries.with(Vec::len)
+  }
+
+  /// Whether no content was received yet.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The position of the shown content in the history, starting at `0` for
+  /// the oldest one.
+  pub fn position(&self) -> usize {
+    self.position.get()
+  }
+
+  /// Whether there is an older content to go back to.
+  pub fn can_go_back(&self) -> bool {
+    self.position() > 0
+  }
+
+  /// Whether there is a newer content to go forward to.
+  pub fn can_go_forward(&self) -> bool {
+    self.position() + 1 < self.len()
   }
+
+  /// Shows the previous content, if any.
+  pub fn back(&self) {
+    if self.can_go_back() {
+      self.position.update(|position| *position -= 1);
+    }
+  }
+
+  /// Shows the next content, if any.
+  pub fn forward(&self) {
+    if self.can_go_forward() {
+      self.position.update(|position| *position += 1);
+    }
+  }
+
+  fn push(&self, children: ChildrenFn) {
+    let position = self.position.get_untracked();
+
+    self.entries.update(|entries| {
+      entries.truncate(position + 1);
+      entries.push(children);
+    });
+
+    self.position.set(self.len_untracked() - 1);
+  }
+
+  fn len_untracked(&self) -> usize {
+    self.entries.with_untracked(Vec::len)
+  }
+
+  fn current(&self) -> Option<ChildrenFn> {
+    let position = self.position.get();
+
+    self.entries.with(|entries| entries.get(position).cloned())
+  }
+}
+
+/// A handle to the HTML of the content rendered by a [`PortalOutput`] given
+/// it as `export`, e.g. for printing just that panel, or exporting it to a
+/// PDF.
+///
+/// The HTML is read from the DOM on demand with [`html`](Self::html), so it
+/// includes whatever the content rendered since it was registered, and is
+/// only available in the browser. [`is_empty`](Self::is_empty) is reactive
+/// over the portal having content, e.g. to disable a print button.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// struct Invoice;
+///
+/// let export = PortalExport::new();
+///
+/// let print = move |_| {
+///   if let Some(html) = export.html() {
+///     // Hand the markup to a print window, or to a PDF service
+///     logging::log!("{html}");
+///   }
+/// };
+///
+/// view! {
+///   <PortalProvider>
+///     <button disabled=move || export.is_empty() on:click=print>
+///       "Print invoice"
+///     </button>
+///
+///     <PortalOutput id=Invoice export=export />
+///
+///     <PortalInput id=Invoice>
+///       <table>"..."</table>
+///     </PortalInput>
+///   </PortalProvider>
+/// };
+///
+/// // There is no DOM to read outside the browser
+/// assert_eq!(export.html(), None);
+/// # runtime.dispose();
+/// ```
+#[derive(Clone, Copy)]
+pub struct PortalExport {
+  wrapper: NodeRef<html::Div>,
+  has_content: RwSignal<bool>,
 }
 
-const CONTEXT_NOT_FOUND_ERROR_MESSAGE: &str =
-  "failed to find `PortalCtx`, make sure you are using `<PortalProvider />` \
-   somewhere near the root of the app";
+impl Default for PortalExport {
+  fn default() -> Self {
+    Self::new()
+  }
+}
 
-#[derive(Clone)]
-struct PortalCtx(StoredValue<Vec<(TypeId, RwSignal<Option<Children>>)>>);
+impl PortalExport {
+  /// Creates a handle, to give to a [`PortalOutput`] as `export`.
+  pub fn new() -> Self {
+    Self {
+      wrapper: create_node_ref(),
+      has_content: create_rw_signal(false),
+    }
+  }
 
-/// The portal provider which allows to use [`PortalInput`] and [`PortalOutput`].
+  /// Whether the portal has no content, or the handle was not given to a
+  /// mounted [`PortalOutput`]. Reactive.
+  pub fn is_empty(&self) -> bool {
+    !self.has_content.get()
+  }
+
+  /// Returns the HTML of the content the output currently renders, i.e. the
+  /// `outerHTML` of each of its top-level nodes, read from the DOM as it is
+  /// now.
+  ///
+  /// Returns [`None`] when the portal has no content, when the output is not
+  /// mounted, and on the server. While crossfading, the HTML includes the
+  /// contents being replaced.
+  pub fn html(&self) -> Option<String> {
+    if !self.has_content.get_untracked() {
+      return None;
+    }
+
+    self
+      .wrapper
+      .get_untracked()
+      .map(|wrapper| (*wrapper).inner_html())
+  }
+
+  /// Keeps track of whether `content` is [`Some`], until the current owner
+  /// is cleaned up.
+  fn follow(&self, content: Signal<Option<ChildrenFn>>) {
+    let has_content = self.has_content;
+
+    create_isomorphic_effect(move |_| {
+      has_content.set(content.with(Option::is_some));
+    });
+
+    on_cleanup(move || {
+      has_content.try_set(false);
+    });
+  }
+}
+
+/// Like [`PortalOutput`], but renders the portal content inside the shadow
+/// root of the `host` element instead of where it is declared, so that the
+/// content's styles are encapsulated, e.g. inside a custom element.
 ///
-/// This must be located somewhere near the root of your component tree, above
-/// anywhere you would like to use portals.
+/// If `host` does not have a shadow root yet, an open one is attached to it.
+///
+/// Content is only rendered in the browser, as there is no shadow DOM on the
+/// server.
 ///
 /// # Examples
 /// ```rust
 /// use leptos::*;
 /// use leptos_declarative::prelude::*;
 ///
-/// # let _ = create_scope(create_runtime(), |cx| {
+/// # let runtime = create_runtime();
 ///
 /// struct PortalId;
 ///
-/// view! { cx,
+/// let host = create_node_ref::<html::Div>();
+///
+/// view! {
 ///   <PortalProvider>
-///     <div>
-///       <h1>"Portal goes here!"</h1>
-///       <PortalOutput id=PortalId />
-///     </div>
+///     <div node_ref=host />
+///     <ShadowPortalOutput id=PortalId host=host />
 ///
 ///     <PortalInput id=PortalId>
-///       <p>"I went through the portal!"</p>
+///       <p>"I live in the shadow DOM!"</p>
 ///     </PortalInput>
 ///   </PortalProvider>
 /// };
-/// # });
+/// # runtime.dispose();
 /// ```
 #[component]
-pub fn PortalProvider(
-  cx: Scope,
-  /// The rest of your app. [`PortalInput`] and [`PortalOutput`] can be used
-  /// anywhere below this point.
-  children: Children,
-) -> impl IntoView {
-  provide_context(cx, PortalCtx(store_value(cx, Default::default())));
+pub fn ShadowPortalOutput<T, El>(
+  /// The type used as an `id`. This must match the `id` of the
+  /// corresponding [`PortalInput`].
+  id: T,
+  /// The element whose shadow root the content is rendered into.
+  host: NodeRef<El>,
+  /// Marks this output as intentionally sharing its `id` with other outputs
+  /// which broadcast, for a strict [`PortalProvider`].
+  #[prop(optional)]
+  broadcast: bool,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
+) -> impl IntoView
+where
+  T: Any,
+  El: ElementDescriptor + Clone + 'static,
+{
+  let portal_ctx = use_portal_ctx().unwrap_or_else(|err| {
+    panic!(
+      "{}: {err}",
+      component_name("ShadowPortalOutput", debug_name)
+    )
+  });
+
+  let id = portal_ctx.id_of(&id);
+
+  portal_ctx.count_output(id);
+
+  #[cfg(debug_assertions)]
+  portal_ctx.add_output(
+    id,
+    broadcast,
+    &component_name("ShadowPortalOutput", debug_name),
+  );
+  #[cfg(not(debug_assertions))]
+  let _ = broadcast;
+
+  if !leptos_dom::is_browser() {
+    return;
+  }
 
-  children(cx)
+  host.on_load(move |host| {
+    let host = host.into_any();
+
+    let shadow_root = host.shadow_root().unwrap_or_else(|| {
+      host
+        .attach_shadow(&web_sys::ShadowRootInit::new(
+          web_sys::ShadowRootMode::Open,
+        ))
+        .expect("failed to attach a shadow root to the portal host")
+    });
+
+    let content = (*html::div().child(render_portal_output(
+      portal_ctx,
+      id,
+      portal_ctx.while_enabled(portal_ctx.get(id)),
+      debug_name,
+    )))
+    .clone();
+
+    shadow_root
+      .append_child(&content)
+      .expect("failed to append to the shadow root of the portal host");
+
+    on_cleanup(move || content.remove());
+  });
 }
 
-/// The portal entry point. Whatever children this component has will be rendered
-/// in the corresponding [`PortalOutput`] with the matching `id`, wherever in your
-/// app that may be.
+/// Like [`PortalOutput`], but renders the portal content inside the body of
+/// the document of the `frame` iframe, e.g. for a sandboxed preview of an
+/// email template authored in the app.
+///
+/// The content is appended to the body in a `<div>`, which is removed again
+/// when this component unmounts. The iframe loading another page, including
+/// its initial `src`, replaces its document, so the content is moved into the
+/// new document whenever the iframe loads.
+///
+/// The document of an iframe showing a page of another origin is not
+/// accessible, in which case nothing is rendered, and a warning is logged in
+/// debug builds. Styles of the app do not apply inside the iframe, so they
+/// need to be added to its document separately.
+///
+/// Content is only rendered in the browser, as there is no iframe document on
+/// the server.
 ///
 /// # Examples
 /// ```rust
 /// use leptos::*;
 /// use leptos_declarative::prelude::*;
 ///
-/// # let _ = create_scope(create_runtime(), |cx| {
+/// # let runtime = create_runtime();
 ///
-/// struct PortalId;
+/// struct Preview;
 ///
-/// view! { cx,
+/// let frame = create_node_ref::<html::Iframe>();
+///
+/// view! {
 ///   <PortalProvider>
-///     <div>
-///       <h1>"Portal goes here!"</h1>
-///       <PortalOutput id=PortalId />
-///     </div>
+///     <iframe node_ref=frame />
+///     <IframePortalOutput id=Preview frame=frame />
 ///
-///     <PortalInput id=PortalId>
-///       <p>"I went through the portal!"</p>
+///     <PortalInput id=Preview>
+///       <h1>"Welcome aboard!"</h1>
 ///     </PortalInput>
 ///   </PortalProvider>
 /// };
-/// # });
+/// # runtime.dispose();
 /// ```
 #[component]
-pub fn PortalInput<T>(
-  cx: Scope,
+pub fn IframePortalOutput<T>(
   /// The type used as an `id`. This must match the `id` of the
-  /// corresponding [`PortalOutput`].
+  /// corresponding [`PortalInput`].
   id: T,
-  /// The children you want to render anywhere the matching [`PortalOutput`]
-  /// is located.
-  children: Children,
+  /// The iframe whose document the content is rendered into.
+  frame: NodeRef<html::Iframe>,
+  /// Marks this output as intentionally sharing its `id` with other outputs
+  /// which broadcast, for a strict [`PortalProvider`].
+  #[prop(optional)]
+  broadcast: bool,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
 ) -> impl IntoView
 where
   T: Any,
 {
-  let portal_ctx =
-    use_context::<PortalCtx>(cx).expect(CONTEXT_NOT_FOUND_ERROR_MESSAGE);
+  let portal_ctx = use_portal_ctx().unwrap_or_else(|err| {
+    panic!(
+      "{}: {err}",
+      component_name("IframePortalOutput", debug_name)
+    )
+  });
 
-  portal_ctx.0.update_value(|portals| {
-    if let Some(pos) = portals
-      .iter()
-      .position(|(type_id, _)| *type_id == id.type_id())
-    {
-      portals[pos].1.set(Some(children));
-    } else {
-      let children = create_rw_signal(cx, Some(children));
+  let id = portal_ctx.id_of(&id);
 
-      portals.push((id.type_id(), children));
-    }
+  portal_ctx.count_output(id);
+
+  #[cfg(debug_assertions)]
+  portal_ctx.add_output(
+    id,
+    broadcast,
+    &component_name("IframePortalOutput", debug_name),
+  );
+  #[cfg(not(debug_assertions))]
+  let _ = broadcast;
+
+  if !leptos_dom::is_browser() {
+    return;
+  }
+
+  frame.on_load(move |frame| {
+    let frame = (*frame).clone();
+
+    let content = web_sys::Element::from(
+      (*html::div().child(render_portal_output(
+        portal_ctx,
+        id,
+        portal_ctx.while_enabled(portal_ctx.get(id)),
+        debug_name,
+      )))
+      .clone(),
+    );
+
+    let mount = {
+      let frame = frame.clone();
+      let content = content.clone();
+
+      move || {
+        // Cross-origin documents are not accessible
+        let body = frame
+          .content_document()
+          .and_then(|document| document.body());
+
+        if let Some(body) = body {
+          body
+            .append_child(&content)
+            .expect("failed to append to the body of the portal iframe");
+        } else {
+          #[cfg(debug_assertions)]
+          logging::warn!(
+            "{}: the document of the iframe is not accessible, e.g. because \
+             it is of another origin, so the portal content is not rendered",
+            component_name("IframePortalOutput", debug_name)
+          );
+        }
+      }
+    };
+
+    mount();
+
+    let on_frame_load = Closure::<dyn Fn()>::new(mount);
+
+    frame
+      .add_event_listener_with_callback(
+        "load",
+        on_frame_load.as_ref().unchecked_ref(),
+      )
+      .expect("failed to listen to the portal iframe loading");
+
+    on_cleanup(move || {
+      let _ = frame.remove_event_listener_with_callback(
+        "load",
+        on_frame_load.as_ref().unchecked_ref(),
+      );
+
+      drop(on_frame_load);
+
+      content.remove();
+    });
   });
 }
 
-/// The portal output point. Whatever children the corresponding [`Portal`} with
-/// matching `id` has, will be rendered here.
+/// Like [`PortalOutput`], but renders the portal content inside the element
+/// of the page with the given `target` id, which does not need to be rendered
+/// by leptos. This allows leptos widgets to be embedded into a page rendered
+/// by something else, such as a server-rendered Rails view, one region at a
+/// time.
+///
+/// The content is appended to the target element in a `<div>`, which is
+/// removed again when this component unmounts, leaving the rest of the target
+/// element untouched.
+///
+/// If there is no element with the `target` id yet, e.g. because the host
+/// page renders it later, it is looked up again on every animation frame for
+/// about a second, after which a warning is logged in debug builds.
+///
+/// Content is only rendered in the browser, as there is no page to look the
+/// target element up in on the server.
 ///
 /// # Examples
 /// ```rust
 /// use leptos::*;
 /// use leptos_declarative::prelude::*;
 ///
-/// # let _ = create_scope(create_runtime(), |cx| {
+/// # let runtime = create_runtime();
 ///
-/// struct PortalId;
+/// struct CartWidget;
 ///
-/// view! { cx,
+/// // The host page has a `<div id="cart-widget"></div>` somewhere
+/// view! {
 ///   <PortalProvider>
-///     <div>
-///       <h1>"Portal goes here!"</h1>
-///       <PortalOutput id=PortalId />
-///     </div>
+///     <BridgePortalOutput id=CartWidget target="cart-widget" />
 ///
-///     <PortalInput id=PortalId>
-///       <p>"I went through the portal!"</p>
+///     <PortalInput id=CartWidget>
+///       <p>"3 items in your cart"</p>
 ///     </PortalInput>
 ///   </PortalProvider>
 /// };
-/// # });
+/// # runtime.dispose();
 /// ```
 #[component]
-pub fn PortalOutput<T>(
-  cx: Scope,
+pub fn BridgePortalOutput<T>(
   /// The type used as an `id`. This must match the `id` of the
   /// corresponding [`PortalInput`].
   id: T,
+  /// The `id` attribute of the element the content is rendered into.
+  #[prop(into)]
+  target: String,
+  /// Marks this output as intentionally sharing its `id` with other outputs
+  /// which broadcast, for a strict [`PortalProvider`].
+  #[prop(optional)]
+  broadcast: bool,
+  /// A name identifying this component in warnings and panic messages.
+  #[prop(optional)]
+  debug_name: Option<&'static str>,
 ) -> impl IntoView
 where
   T: Any,
 {
-  let portal_ctx =
-    use_context::<PortalCtx>(cx).expect(CONTEXT_NOT_FOUND_ERROR_MESSAGE);
+  let portal_ctx = use_portal_ctx().unwrap_or_else(|err| {
+    panic!(
+      "{}: {err}",
+      component_name("BridgePortalOutput", debug_name)
+    )
+  });
 
-  let mut children = None;
+  let id = portal_ctx.id_of(&id);
 
-  portal_ctx.0.update_value(|portals| {
-    let children_signal = if let Some(pos) = portals
-      .iter()
-      .position(|(type_id, _)| *type_id == id.type_id())
-    {
-      portals[pos].1
-    } else {
-      let children = create_rw_signal(cx, None);
+  portal_ctx.count_output(id);
 
-      portals.push((id.type_id(), children));
+  #[cfg(debug_assertions)]
+  portal_ctx.add_output(
+    id,
+    broadcast,
+    &component_name("BridgePortalOutput", debug_name),
+  );
+  #[cfg(not(debug_assertions))]
+  let _ = broadcast;
 
-      children
-    };
+  if !leptos_dom::is_browser() {
+    return;
+  }
+
+  let owner = Owner::current();
+  let unmounted = Rc::new(Cell::new(false));
+  let mounted = Rc::new(Cell::new(None::<web_sys::Element>));
+
+  let mount = Rc::new({
+    let mounted = mounted.clone();
+
+    move |target: web_sys::Element| {
+      let render = || {
+        (*html::div().child(render_portal_output(
+          portal_ctx,
+          id,
+          portal_ctx.while_enabled(portal_ctx.get(id)),
+          debug_name,
+        )))
+        .clone()
+      };
 
-    children = Some(children_signal);
+      let content = match owner {
+        Some(owner) => with_owner(owner, render),
+        None => render(),
+      };
+
+      target
+        .append_child(&content)
+        .expect("failed to append to the portal bridge target");
+
+      mounted.set(Some(content.into()));
+    }
   });
 
-  let children = children.unwrap();
+  mount_when_found(target, 0, unmounted.clone(), mount, debug_name);
 
-  move || {
-    children.with(|children| {
-      if let Some(children) = children {
-        children(cx).into_view(cx)
-      } else {
-        ().into_view(cx)
-      }
-    })
+  on_cleanup(move || {
+    unmounted.set(true);
+
+    if let Some(content) = mounted.take() {
+      content.remove();
+    }
+  });
+}
+
+/// How many animation frames [`BridgePortalOutput`] looks for its target
+/// element before giving up, which is about a second.
+const BRIDGE_TARGET_ATTEMPTS: u32 = 60;
+
+/// Calls `mount` with the element with the `target` id, looking it up again
+/// on the next animation frame while it does not exist, unless `unmounted`.
+fn mount_when_found(
+  target: String,
+  attempt: u32,
+  unmounted: Rc<Cell<bool>>,
+  mount: Rc<dyn Fn(web_sys::Element)>,
+  debug_name: Option<&'static str>,
+) {
+  if unmounted.get() {
+    return;
+  }
+
+  if let Some(element) = document().get_element_by_id(&target) {
+    mount(element);
+  } else if attempt < BRIDGE_TARGET_ATTEMPTS {
+    request_animation_frame(move || {
+      mount_when_found(target, attempt + 1, unmounted, mount, debug_name)
+    });
+  } else {
+    #[cfg(debug_assertions)]
+    logging::warn!(
+      "{}: there is no element with the id `{target}` to render the portal \
+       into, make sure the page renders it",
+      component_name("BridgePortalOutput", debug_name)
+    );
+    #[cfg(not(debug_assertions))]
+    let _ = debug_name;
+  }
+}
+
+/// A portal entry point which needs neither a [`PortalProvider`] nor a
+/// [`PortalOutput`]. Whatever children this component has will be rendered
+/// inside a root element at the end of `document.body`, which is created the
+/// first time it is needed and shared by every [`GlobalPortalInput`].
+///
+/// This is the simplest way to render app-wide overlays, such as toasts or
+/// modals, above everything else. It is completely independent of
+/// [`PortalProvider`], so both kinds of portals can be used side by side.
+///
+/// Content is only rendered in the browser, as there is no `document.body`
+/// to render into on the server.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+///
+/// view! {
+///   <div>
+///     <GlobalPortalInput>
+///       <p class="toast">"Saved!"</p>
+///     </GlobalPortalInput>
+///   </div>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn GlobalPortalInput(
+  /// The children you want to render at the end of `document.body`.
+  children: Children,
+) -> impl IntoView {
+  if !leptos_dom::is_browser() {
+    return;
+  }
+
+  let content = (*html::div().child(children())).clone();
+
+  global_portal_root()
+    .append_child(&content)
+    .expect("failed to append to the global portal root");
+
+  on_cleanup(move || content.remove());
+}
+
+/// Gets the root element [`GlobalPortalInput`] renders into, creating it if it
+/// does not exist yet.
+fn global_portal_root() -> web_sys::Element {
+  let document = document();
+
+  if let Some(root) = document.get_element_by_id(GLOBAL_PORTAL_ROOT_ID) {
+    return root;
   }
+
+  let root = document
+    .create_element("div")
+    .expect("failed to create the global portal root");
+
+  root.set_id(GLOBAL_PORTAL_ROOT_ID);
+
+  document
+    .body()
+    .expect("failed to find `document.body`")
+    .append_child(&root)
+    .expect("failed to append the global portal root to `document.body`");
+
+  root
 }
@@ -0,0 +1,72 @@
+//! Route conditions for [`leptos`], built on [`leptos_router`].
+//!
+//! Please refer to [`route_matches`] for usage examples.
+
+use leptos::*;
+use leptos_router::use_location;
+
+/// Returns whether the current path matches `pattern`, usable directly as the
+/// condition of an [`If`](crate::if_::If) or [`ElseIf`](crate::if_::ElseIf).
+///
+/// Both the pattern and the path are split into `/`-separated segments,
+/// ignoring empty ones, so leading and trailing slashes do not matter. The
+/// path matches when every segment matches, in order:
+///
+/// - A plain segment matches the exact same segment.
+/// - A `:param` segment matches any single segment.
+/// - A `*` or `*rest` segment matches any remaining segments, including none,
+///   which makes the match a prefix match, e.g. `/settings/*` matches
+///   `/settings`, `/settings/profile` and `/settings/profile/avatar`.
+///
+/// Otherwise the match is exact, so `/settings` does not match
+/// `/settings/profile`. The query string and hash are ignored.
+///
+/// This must be called under a [`Router`](leptos_router::Router).
+///
+/// # Examples
+/// ```rust,no_run
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+/// use leptos_router::*;
+///
+/// # let runtime = create_runtime();
+/// view! {
+/// <Router>
+///   <nav>
+///     <If signal=route_matches("/settings/*")>
+///       <Then>"Settings"</Then>
+///       <ElseIf signal=route_matches("/users/:id")>"User profile"</ElseIf>
+///       <Else>"Home"</Else>
+///     </If>
+///   </nav>
+/// </Router>
+/// };
+/// # runtime.dispose();
+/// ```
+pub fn route_matches(pattern: &str) -> Signal<bool> {
+  let pattern = pattern.to_owned();
+  let location = use_location();
+
+  Signal::derive(move || {
+    location.pathname.with(|path| path_matches(&pattern, path))
+  })
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+  let mut pattern = segments(pattern);
+  let mut path = segments(path);
+
+  loop {
+    match (pattern.next(), path.next()) {
+      (Some(pattern), _) if pattern.starts_with('*') => return true,
+      (Some(pattern), Some(segment))
+        if pattern.starts_with(':') || pattern == segment => {}
+      (None, None) => return true,
+      _ => return false,
+    }
+  }
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+  path.split('/').filter(|segment| !segment.is_empty())
+}
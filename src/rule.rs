@@ -0,0 +1,238 @@
+//! Conditions built from serialized rules for [`leptos`], e.g. rules stored
+//! by a CMS, rather than written as Rust closures.
+//!
+//! Please refer to [`Rule`] for usage examples.
+
+use leptos::*;
+use serde::{
+  Deserialize,
+  Serialize,
+};
+use std::{
+  cmp::Ordering,
+  collections::HashMap,
+};
+
+/// A condition over named values, which deserializes from data such as
+/// JSON, and can be turned into a condition for [`If`](crate::if_::If) with
+/// [`Rule::into_signal`].
+///
+/// Rules are externally tagged, in `snake_case`:
+///
+/// ```json
+/// {
+///   "and": [
+///     { "compare": { "name": "role", "op": "eq", "value": "admin" } },
+///     { "not": { "compare": { "name": "unread", "op": "gt", "value": 99 } } }
+///   ]
+/// }
+/// ```
+///
+/// New kinds of rules may be added, so the enum is non-exhaustive.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// let (role, set_role) = create_signal(RuleValue::from("admin"));
+/// let (unread, _) = create_signal(RuleValue::from(3.0));
+///
+/// let context = RuleContext::new()
+///   .value("role", role)
+///   .value("unread", unread);
+///
+/// // Usually deserialized
+/// let rule = Rule::And(vec![
+///   Rule::Compare {
+///     name: "role".to_string(),
+///     op: CompareOp::Eq,
+///     value: "admin".into(),
+///   },
+///   Rule::Not(Box::new(Rule::Compare {
+///     name: "unread".to_string(),
+///     op: CompareOp::Gt,
+///     value: 99.0.into(),
+///   })),
+/// ]);
+///
+/// let is_shown = rule.into_signal(context);
+///
+/// assert!(is_shown.get());
+///
+/// set_role.set("editor".into());
+///
+/// assert!(!is_shown.get());
+///
+/// view! {
+/// <If signal=is_shown>
+///   <Then>"Admin tools"</Then>
+/// </If>
+/// };
+/// # runtime.dispose();
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Rule {
+  /// True when all of the rules are, including when there are none.
+  And(Vec<Rule>),
+  /// True when any of the rules is, so false when there are none.
+  Or(Vec<Rule>),
+  /// True when the rule is false.
+  Not(Box<Rule>),
+  /// Compares the named value of the [`RuleContext`] with `value`.
+  ///
+  /// False when the context has no value with that name.
+  Compare {
+    /// The name of the value in the [`RuleContext`].
+    name: String,
+    /// How the named value is compared with `value`.
+    op: CompareOp,
+    /// The value to compare the named value with.
+    value: RuleValue,
+  },
+}
+
+impl Rule {
+  /// Evaluates the rule against the current values of `context`, reactive
+  /// over the values it reads.
+  pub fn evaluate(&self, context: &RuleContext) -> bool {
+    match self {
+      Self::And(rules) => rules.iter().all(|rule| rule.evaluate(context)),
+      Self::Or(rules) => rules.iter().any(|rule| rule.evaluate(context)),
+      Self::Not(rule) => !rule.evaluate(context),
+      Self::Compare { name, op, value } => {
+        let Some(named) = context.values.get(name) else {
+          #[cfg(debug_assertions)]
+          logging::warn!(
+            "rule compares the value `{name}`, which is not in the \
+             `RuleContext`, so it is false"
+          );
+
+          return false;
+        };
+
+        named.with(|named| op.compare(named, value))
+      }
+    }
+  }
+
+  /// Returns a signal to whether the rule holds, which is updated whenever
+  /// the values of `context` it reads change, e.g. to use as the condition
+  /// of an [`If`](crate::if_::If).
+  pub fn into_signal(self, context: RuleContext) -> Signal<bool> {
+    create_memo(move |_| self.evaluate(&context)).into()
+  }
+}
+
+/// How [`Rule::Compare`] compares a named value with its `value`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum CompareOp {
+  /// Equal to.
+  Eq,
+  /// Not equal to.
+  Ne,
+  /// Less than.
+  Lt,
+  /// Less than or equal to.
+  Le,
+  /// Greater than.
+  Gt,
+  /// Greater than or equal to.
+  Ge,
+}
+
+impl CompareOp {
+  /// Compares `lhs` with `rhs`. Values of different kinds are never equal,
+  /// nor ordered, so only [`Ne`](Self::Ne) holds for them.
+  pub fn compare(self, lhs: &RuleValue, rhs: &RuleValue) -> bool {
+    let ordering = lhs.partial_cmp(rhs);
+
+    match self {
+      Self::Eq => ordering == Some(Ordering::Equal),
+      Self::Ne => ordering != Some(Ordering::Equal),
+      Self::Lt => ordering == Some(Ordering::Less),
+      Self::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+      Self::Gt => ordering == Some(Ordering::Greater),
+      Self::Ge => {
+        matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+      }
+    }
+  }
+}
+
+/// A value which rules compare, deserialized from the corresponding JSON
+/// value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleValue {
+  /// A boolean.
+  Bool(bool),
+  /// A number.
+  Number(f64),
+  /// A string.
+  String(String),
+}
+
+impl PartialOrd for RuleValue {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    match (self, other) {
+      (Self::Bool(lhs), Self::Bool(rhs)) => lhs.partial_cmp(rhs),
+      (Self::Number(lhs), Self::Number(rhs)) => lhs.partial_cmp(rhs),
+      (Self::String(lhs), Self::String(rhs)) => lhs.partial_cmp(rhs),
+      _ => None,
+    }
+  }
+}
+
+impl From<bool> for RuleValue {
+  fn from(value: bool) -> Self {
+    Self::Bool(value)
+  }
+}
+
+impl From<f64> for RuleValue {
+  fn from(value: f64) -> Self {
+    Self::Number(value)
+  }
+}
+
+impl From<&str> for RuleValue {
+  fn from(value: &str) -> Self {
+    Self::String(value.to_string())
+  }
+}
+
+impl From<String> for RuleValue {
+  fn from(value: String) -> Self {
+    Self::String(value)
+  }
+}
+
+/// The named values [`Rule`]s are evaluated against.
+#[derive(Clone, Default)]
+pub struct RuleContext {
+  values: HashMap<String, Signal<RuleValue>>,
+}
+
+impl RuleContext {
+  /// Creates a context with no values.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds the value `name`, replacing any value with the same name.
+  pub fn value(
+    mut self,
+    name: impl Into<String>,
+    value: impl Into<Signal<RuleValue>>,
+  ) -> Self {
+    self.values.insert(name.into(), value.into());
+
+    self
+  }
+}
@@ -1,3 +1,295 @@
+//! Building blocks for writing components in the style of this crate.
+//!
+//! Components such as [`If`](crate::if_::If) and
+//! [`Match`](crate::match_::Match) find their branches by looking for
+//! [`Transparent`] children carrying a known payload, which is what
+//! [`collect_transparent`] does.
+
+use crate::anchor::Rect;
+use leptos::*;
+use leptos_dom::{
+  helpers::TimeoutHandle,
+  Transparent,
+};
+use std::{
+  marker::PhantomData,
+  ops::Deref,
+  time::Duration,
+};
+use wasm_bindgen::{
+  closure::Closure,
+  JsCast,
+};
+
 macro_rules! api_planning {
   ($($tt:tt)*) => {};
 }
+
+/// Formats the name of a component for use in warnings and panic messages,
+/// including its `debug_name`, if it was given one.
+pub(crate) fn component_name(name: &str, debug_name: Option<&str>) -> String {
+  if let Some(debug_name) = debug_name {
+    format!("`<{name} debug_name=\"{debug_name}\" />`")
+  } else {
+    format!("`<{name} />`")
+  }
+}
+
+/// Renders an invisible spacer of at least `min_height`, any CSS length, while
+/// `empty` is true, so that content appearing there later does not shift the
+/// layout.
+pub(crate) fn reserve_space(
+  min_height: &'static str,
+  empty: impl Fn() -> bool + 'static,
+) -> View {
+  (move || {
+    empty().then(|| {
+      view! { <div aria-hidden="true" style:min-height=min_height /> }
+    })
+  })
+  .into_view()
+}
+
+/// Calls `on_measure` with the bounding rect of `node_ref` whenever its size
+/// changes, until the current reactive owner is cleaned up.
+pub(crate) fn observe_size(
+  node_ref: NodeRef<html::Div>,
+  on_measure: Callback<Rect>,
+) {
+  if !leptos_dom::is_browser() {
+    return;
+  }
+
+  node_ref.on_load(move |element| {
+    let element = (*element.into_any()).clone();
+
+    let on_resize = Closure::<dyn Fn()>::new({
+      let element = element.clone();
+
+      move || on_measure.call(element.get_bounding_client_rect().into())
+    });
+
+    let observer =
+      web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref())
+        .expect("failed to create a `ResizeObserver`");
+
+    observer.observe(&element);
+
+    on_cleanup(move || {
+      observer.disconnect();
+
+      drop(on_resize);
+    });
+  });
+}
+
+/// Returns the first element among the top-level nodes of `view`, looking
+/// inside components and fragments but not inside dynamic children, once it
+/// is mounted, in the browser only.
+pub(crate) fn first_element(view: &View) -> Option<web_sys::Element> {
+  match view {
+    View::Element(_) => {
+      let element = view.clone().into_html_element().ok()?;
+      let element: &web_sys::HtmlElement = &element;
+
+      Some(element.clone().into())
+    }
+    View::Component(component) => {
+      component.children.iter().find_map(first_element)
+    }
+    _ => None,
+  }
+}
+
+/// Returns a signal which follows `source`, but changes at most once per
+/// `interval`: a change is applied right away if none was during the last
+/// `interval`, or else once the interval ends, with the latest value of
+/// `source` by then, so that the last value is always applied.
+///
+/// Changes are applied right away on the server, where there is no time to
+/// wait for.
+pub(crate) fn throttle<T: Clone + 'static>(
+  source: Signal<T>,
+  interval: Duration,
+) -> Signal<T> {
+  let throttled = create_rw_signal(source.get_untracked());
+  // The timeout ending the current interval, if any, and whether `source`
+  // changed during it
+  let current_interval = store_value((None::<TimeoutHandle>, false));
+
+  create_isomorphic_effect(move |ran_before: Option<()>| {
+    source.track();
+
+    // The initial value is already applied
+    if ran_before.is_none() {
+      return;
+    }
+
+    if !leptos_dom::is_browser() {
+      throttled.set(source.get_untracked());
+
+      return;
+    }
+
+    if current_interval.with_value(|(timeout, _)| timeout.is_some()) {
+      current_interval.update_value(|(_, changed)| *changed = true);
+    } else {
+      apply_throttled(source, throttled, current_interval, interval);
+    }
+  });
+
+  on_cleanup(move || {
+    let timeout = current_interval
+      .try_update_value(|(timeout, _)| timeout.take())
+      .flatten();
+
+    if let Some(timeout) = timeout {
+      timeout.clear();
+    }
+  });
+
+  throttled.into()
+}
+
+/// Applies the latest value of `source` to `throttled`, and starts an interval
+/// at the end of which changes made during it are applied.
+fn apply_throttled<T: Clone + 'static>(
+  source: Signal<T>,
+  throttled: RwSignal<T>,
+  current_interval: StoredValue<(Option<TimeoutHandle>, bool)>,
+  interval: Duration,
+) {
+  throttled.set(source.get_untracked());
+
+  let timeout = set_timeout_with_handle(
+    move || {
+      let changed = current_interval.try_update_value(|(timeout, changed)| {
+        *timeout = None;
+
+        std::mem::take(changed)
+      });
+
+      if changed == Some(true) {
+        apply_throttled(source, throttled, current_interval, interval);
+      }
+    },
+    interval,
+  );
+
+  current_interval.set_value((timeout.ok(), false));
+}
+
+/// Records the [`metrics`](crate::metrics) of a conditional, or does nothing
+/// without the `metrics` feature.
+#[derive(Clone)]
+pub(crate) struct MetricsRecorder {
+  #[cfg(feature = "metrics")]
+  name: std::rc::Rc<str>,
+}
+
+impl MetricsRecorder {
+  pub(crate) fn new(name: &str, debug_name: Option<&str>) -> Self {
+    #[cfg(not(feature = "metrics"))]
+    let _ = (name, debug_name);
+
+    Self {
+      #[cfg(feature = "metrics")]
+      name: component_name(name, debug_name).into(),
+    }
+  }
+
+  /// Records that a branch was selected.
+  pub(crate) fn evaluation(&self) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::record(&self.name, |metrics| metrics.evaluations += 1);
+  }
+
+  /// Records that a branch was built.
+  pub(crate) fn render(&self) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::record(&self.name, |metrics| metrics.renders += 1);
+  }
+}
+
+/// A [`Transparent`] view known to carry a `T`, which it derefs to.
+///
+/// Cloning it is cheap, as it only clones the underlying [`Transparent`].
+pub struct TypedTransparent<T> {
+  transparent: Transparent,
+  _payload: PhantomData<T>,
+}
+
+impl<T> Clone for TypedTransparent<T> {
+  fn clone(&self) -> Self {
+    Self {
+      transparent: self.transparent.clone(),
+      _payload: PhantomData,
+    }
+  }
+}
+
+impl<T: 'static> Deref for TypedTransparent<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    self
+      .transparent
+      .downcast_ref()
+      .expect("`TypedTransparent` always carries its payload type")
+  }
+}
+
+/// Collects, in order, the children of `fragment` which are [`Transparent`]
+/// views carrying a `T`, ignoring all other children.
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::util::collect_transparent;
+///
+/// struct Tab(&'static str);
+///
+/// impl IntoView for Tab {
+///   fn into_view(self) -> View {
+///     View::Transparent(leptos_dom::Transparent::new(self))
+///   }
+/// }
+///
+/// # let runtime = create_runtime();
+/// let fragment = Fragment::new(vec![
+///   Tab("Home").into_view(),
+///   "not a tab".into_view(),
+///   Tab("Settings").into_view(),
+/// ]);
+///
+/// let tabs = collect_transparent::<Tab>(&fragment);
+///
+/// assert_eq!(
+///   tabs.iter().map(|tab| tab.0).collect::<Vec<_>>(),
+///   ["Home", "Settings"],
+/// );
+/// # runtime.dispose();
+/// ```
+pub fn collect_transparent<T: 'static>(
+  fragment: &Fragment,
+) -> Vec<TypedTransparent<T>> {
+  transparent_children(fragment)
+    .filter(|transparent| transparent.downcast_ref::<T>().is_some())
+    .map(|transparent| TypedTransparent {
+      transparent: transparent.clone(),
+      _payload: PhantomData,
+    })
+    .collect()
+}
+
+/// Iterates, in order, over the children of `fragment` which are
+/// [`Transparent`] views, whatever they carry, for children of several
+/// payload types which [`collect_transparent`] would split apart.
+pub(crate) fn transparent_children(
+  fragment: &Fragment,
+) -> impl Iterator<Item = &Transparent> {
+  fragment
+    .as_children()
+    .iter()
+    .filter_map(View::as_transparent)
+}
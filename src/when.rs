@@ -1,34 +1,85 @@
-// use leptos::*;
+//! A strict two-way conditional for [`leptos`].
+//!
+//! Please refer to [`When`] for usage examples.
 
-api_planning! {
-  let (any_signal, _) = create_signal(cx, "apple");
+use crate::condition::IntoCondition;
+use leptos::*;
 
-  view! { cx,
-    <When signal=any_signal>
-      <Is f=|signal_value| signal_value == "apple">
-        "show this"
-      </Is>
-      <Is f=|signal_value| signal_value == "oranges">
-        "show that"
-      </Is>
-      <Otherwise>
-        "fallback"
-      </Otherwise>
-    </When>
+/// Shows one of exactly two branches, depending on a condition.
+///
+/// Unlike [`If`](crate::if_::If), there are no `ElseIf` chains, and both
+/// branches are slots which the compiler requires, so forgetting one is a
+/// compile error rather than a branch silently rendering nothing.
+///
+/// The condition accepts anything implementing [`IntoCondition`], like
+/// [`If`](crate::if_::If).
+///
+/// # Examples
+/// ```rust
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// let html = leptos::ssr::render_to_string(|| {
+///   let (logged_in, _) = create_signal(false);
+///
+///   view! {
+///   <When signal=logged_in>
+///     <WhenTrue slot>"Welcome back!"</WhenTrue>
+///     <Otherwise slot>"Please log in."</Otherwise>
+///   </When>
+///   }
+/// });
+///
+/// assert!(html.contains("Please log in."));
+/// ```
+///
+/// Leaving out a branch fails to compile:
+/// ```rust,compile_fail
+/// use leptos::*;
+/// use leptos_declarative::prelude::*;
+///
+/// # let runtime = create_runtime();
+/// view! {
+/// <When signal=true>
+///   <WhenTrue slot>"Welcome back!"</WhenTrue>
+/// </When>
+/// };
+/// # runtime.dispose();
+/// ```
+#[component]
+pub fn When<C>(
+  /// The condition.
+  signal: C,
+  /// What to show when the condition is true.
+  when_true: WhenTrue,
+  /// What to show when the condition is false.
+  otherwise: Otherwise,
+) -> impl IntoView
+where
+  C: IntoCondition + 'static,
+{
+  // Memoize the signal
+  let signal = create_memo(move |_| signal.evaluate());
+
+  move || {
+    if signal.get() {
+      (when_true.children)().into_view()
+    } else {
+      (otherwise.children)().into_view()
+    }
   }
 }
 
-// #[component]
-// fn When<T>(cx: Scope, signal: Signal<T>) -> impl IntoView
-// where
-//   T: 'static,
-// {
-//   todo!()
-// }
+/// The branch of a [`When`] shown when its condition is true.
+#[slot]
+pub struct WhenTrue {
+  /// What you want to show.
+  children: ChildrenFn,
+}
 
-// #[component(transparent)]
-// fn Is<F, T>(cx: Scope, f: F) -> impl IntoView
-// where
-//   F: Fn(T) -> bool + 'static,
-// {
-// }
+/// The branch of a [`When`] shown when its condition is false.
+#[slot]
+pub struct Otherwise {
+  /// What you want to show.
+  children: ChildrenFn,
+}